@@ -2,24 +2,47 @@ from enum import Enum
 from typing import Optional, Tuple
 from fractions import Fraction
 
-from .ever_playground import Cell, Builder, Slice, Dictionary, NaN, Gas, Continuation, ContinuationType, SaveList, VmState, VmResult, assemble, runvm_generic
+from .ever_playground import Address, Cell, CellDiff, Builder, Slice, Dictionary, Boc, BocInfo, MerkleProof, MerkleUpdate, NaN, Gas, Continuation, ContinuationType, SaveList, VmState, VmResult, Vm, TraceStep, GasProfile, Coverage, AssemblerError, Instruction, assemble, assemble_debug, disassemble, disassemble_instructions, runvm_generic
+from .ever_playground import PlaygroundError, CellOverflowError, CellUnderflowError, BocError, DictionaryError, InvalidBitstringError, VmExecutionError
 from .ever_playground import ed25519_new_keypair, ed25519_secret_to_public, ed25519_sign, ed25519_check_signature
 
 __all__ = [
+    "Address",
     "Cell",
+    "CellDiff",
     "Builder",
     "Slice",
     "Dictionary",
+    "Boc",
+    "BocInfo",
+    "MerkleProof",
+    "MerkleUpdate",
     "NaN",
     "ExceptionCode",
+    "PlaygroundError",
+    "CellOverflowError",
+    "CellUnderflowError",
+    "BocError",
+    "DictionaryError",
+    "InvalidBitstringError",
+    "VmExecutionError",
     "StateInit",
+    "AssemblerError",
     "assemble",
+    "assemble_debug",
+    "Instruction",
+    "disassemble",
+    "disassemble_instructions",
     "Gas",
     "ContinuationType",
     "Continuation",
     "SaveList",
     "VmState",
     "VmResult",
+    "Vm",
+    "TraceStep",
+    "GasProfile",
+    "Coverage",
     "runvm_generic",
     "runvm",
     "parse_smc_addr",
@@ -45,8 +68,13 @@ def runvm(code, stack, **kwargs) -> VmResult:
      - gas_credit: int
      - gas_limit_max: int
      - gas_price: int
-     - trace: bool
+     - trace: bool or "collect"
      - libs: list
+     - hook: callable invoked with a TraceStep after every instruction;
+       returning an int stops the execution with that exit code
+     - breakpoints: list of (code cell hash, bit offset) tuples or mnemonics
+     - profile: bool
+     - coverage: Coverage
 
     Returns VmResult.    
     """
@@ -66,11 +94,15 @@ def runvm(code, stack, **kwargs) -> VmResult:
     gas_limit_max = 1_000_000_000
     gas_price = 10
     libs = []
+    hook = None
+    breakpoints = []
+    profile = False
+    coverage = None
     for key, value in kwargs.items():
         if key == "capabilities":
             capabilities = int(value)
         elif key == "trace":
-            trace = bool(value)
+            trace = value if isinstance(value, str) else bool(value)
         elif key == "c4":
             regs.put(4, value)
         elif key == "c7":
@@ -85,11 +117,19 @@ def runvm(code, stack, **kwargs) -> VmResult:
             gas_price = int(value)
         elif key == "libs":
             libs = value
+        elif key == "hook":
+            hook = value
+        elif key == "breakpoints":
+            breakpoints = value
+        elif key == "profile":
+            profile = bool(value)
+        elif key == "coverage":
+            coverage = value
         else:
             raise Exception("Unknown parameter {}".format(key))
 
     state = VmState(cc, regs, Gas(gas_limit, gas_credit, gas_limit_max, gas_price))
-    return runvm_generic(state, capabilities, trace, libs)
+    return runvm_generic(state, capabilities, trace, libs, hook, breakpoints, profile, coverage)
 
 class ExceptionCode(Enum):
     """TVM exception code."""
@@ -110,7 +150,10 @@ class ExceptionCode(Enum):
     IllegalInstruction = 14
 
 def parse_smc_addr(addr_string: str) -> Tuple[int, int]:
-    """Parses smart-contract address"""
+    """Parses smart-contract address in either raw or user-friendly form"""
+    if ":" not in addr_string:
+        addr, _, _ = Address.from_friendly(addr_string)
+        return addr.workchain, addr.address
     addr_pair = addr_string.split(":")
     assert(len(addr_pair) == 2)
     wc = int(addr_pair[0])
@@ -180,10 +223,8 @@ class StateInit:
             self.tock = bool(s.u(1))
         else:
             self.tick = self.tock = False
-        if s.u(1):
-            self.code = s.r()
-        if s.u(1):
-            self.data = s.r()
+        self.code = s.maybe_ref()
+        self.data = s.maybe_ref()
         if s.u(1):
             self.library = Dictionary.deserialize(256, s)
         return self
@@ -199,14 +240,7 @@ class StateInit:
             b.i(1, 1).i(1, self.tick).i(1, self.tock)
         else:
             b.i(1, 0)
-        if self.code is None:
-            b.i(1, 0)
-        else:
-            b.i(1, 1).r(self.code)
-        if self.data is None:
-            b.i(1, 0)
-        else:
-            b.i(1, 1).r(self.data)
+        b.maybe_ref(self.code).maybe_ref(self.data)
         if self.library is None:
             b.i(1, 0)
         else:
@@ -229,20 +263,12 @@ class Currency:
 
     def deserialize(self, s: Slice):
         """Deserializes Currency from the ``s`` slice."""
-        len = s.u(4)
-        if len == 0:
-            self.value = 0
-        else:
-            self.value = s.u(len * 8)
+        self.value = s.coins()
         return self
 
     def serialize(self) -> Builder:
         """Serializes Currency into Builder."""
-        if self.value == 0:
-            return Builder().i(4, 0)
-        len = (self.value.bit_length() + 7) >> 3
-        assert(len < 16)
-        return Builder().i(4, len).i(len * 8, self.value)
+        return Builder().coins(self.value)
 
     def __str__(self) -> str:
         return str(float(Fraction(self.value) / self.FACTOR))
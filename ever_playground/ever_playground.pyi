@@ -1,4 +1,78 @@
-from typing import Optional, Tuple
+from typing import Callable, Iterable, Optional, Tuple, Union
+
+class PlaygroundError(RuntimeError):
+    """Base class of ever_playground errors."""
+
+class CellOverflowError(PlaygroundError):
+    """Cell data or references overflow."""
+
+class CellUnderflowError(PlaygroundError):
+    """Not enough data or references in a cell."""
+
+class BocError(PlaygroundError):
+    """Bag of cells serialization error."""
+
+class DictionaryError(PlaygroundError):
+    """Dictionary error."""
+
+class InvalidBitstringError(PlaygroundError):
+    """Malformed bitstring."""
+
+class VmExecutionError(PlaygroundError):
+    """TVM failed to execute the code."""
+
+class Address:
+    """
+    A message address of one of the kinds: ``none`` (addr_none), ``extern`` (addr_extern),
+    ``std`` (addr_std) and ``var`` (addr_var). Internal addresses may carry an anycast rewrite prefix.
+
+    The constructor parses the raw form: ``""`` for addr_none, ``":bits"`` for addr_extern,
+    ``"wc:bits"`` for internal addresses and ``"prefix:wc:bits"`` for anycast ones, where ``bits``
+    are hex bitstrings, e.g. ``"0:83dfd552e63729b472fcbcc8c45ebcc6691702558b68ec7527e1ba403a0f31a8"``.
+    Internal addresses of 256 bits in workchains -128..127 are std, the others are var.
+    The user-friendly form of std addresses is accepted as well.
+    """
+    kind: str
+    workchain: Optional[int]
+    """Workchain of an internal address, ``None`` for external ones."""
+    bits: Slice
+    """Bits of the address."""
+    address: int
+    """Bits of the address as an unsigned integer."""
+    anycast: Optional[Slice]
+    """Anycast rewrite prefix."""
+
+    def __init__(self, text: str) -> None: ...
+
+    @staticmethod
+    def none() -> Address: ...
+
+    @staticmethod
+    def external(bits: Slice) -> Address: ...
+
+    @staticmethod
+    def std(workchain: int, address: int, anycast: Optional[Slice] = None) -> Address: ...
+
+    @staticmethod
+    def var(workchain: int, bits: Slice, anycast: Optional[Slice] = None) -> Address: ...
+
+    def is_internal(self) -> bool: ...
+
+    @staticmethod
+    def from_friendly(text: str) -> Tuple[Address, bool, bool]:
+        """
+        Parses the user-friendly form of a std address: 36 bytes of the flags, the workchain,
+        the address and its CRC16 encoded with either the standard or the url-safe base64 alphabet.
+        Returns the address along with its bounceable and testnet flags.
+        """
+
+    def to_friendly(self, bounceable: bool = True, testnet: bool = False, url_safe: bool = True) -> str:
+        """
+        Returns the user-friendly form of a std address without anycast.
+        """
+
+    def __str__(self) -> str:
+        """Returns the raw form of the address."""
 
 class Cell:
     """
@@ -15,13 +89,66 @@ class Cell:
         Constructs empty cell.
         """
 
-    def write(self, flags: int) -> bytes:
+    @staticmethod
+    def exotic(bitstring: str, *args: Cell) -> Cell:
+        """
+        Constructs an exotic cell. Its type is defined by the first byte of the data:
+        1 for pruned branch, 2 for library reference, 3 for Merkle proof, and 4 for Merkle update.
+        """
+
+    @staticmethod
+    def pruned_branch(cell: Cell, merkle_depth: int = 0) -> Cell:
+        """
+        Constructs a pruned branch cell standing for ``cell`` at the ``merkle_depth`` level,
+        which can't be below the level of ``cell``.
+        """
+
+    @staticmethod
+    def library(hash: int) -> Cell:
+        """
+        Constructs a library reference cell to the library cell with the repr ``hash``.
+        """
+
+    @staticmethod
+    def merkle_proof(cell: Cell) -> Cell:
+        """
+        Constructs a Merkle proof cell over ``cell``, which usually contains pruned branches.
+        """
+
+    @staticmethod
+    def merkle_update(old: Cell, new: Cell) -> Cell:
+        """
+        Constructs a Merkle update cell from the ``old`` tree to the ``new`` one.
+        """
+
+    def make_merkle_proof(self, visited: Union[Iterable[int], Callable[[Cell], bool]]) -> MerkleProof:
+        """
+        Makes a Merkle proof of the tree rooted at this cell. Every subtree not ``visited`` is
+        replaced with a pruned branch. ``visited`` is either a collection of repr hashes of the cells
+        to keep, or a callable deciding whether to keep the given cell. The root is always kept.
+        """
+
+    def is_exotic(self) -> bool:
+        """
+        Returns whether the cell is exotic.
+        """
+
+    def cell_type(self) -> str:
+        """
+        Returns the type of the cell: "ordinary", "pruned_branch", "library",
+        "merkle_proof" or "merkle_update".
+        """
+
+    def write(self, flags: int, fift: bool = False) -> bytes:
         """
         Writes the cell to boc bytes.
 
         Bits of the ``flags`` parameter have the following effect:
         - +1 enables bag-of-cells index creation (useful for lazy deserialization of large bags of cells).
         - +2 includes the CRC32-C of all data into the serialization (useful for checking data integrity).
+
+        With ``fift`` set, the cells are ordered the way Fift's ``boc+>B`` does it,
+        so that the output is byte-for-byte identical to the one produced by Fift.
         """
 
     @staticmethod
@@ -30,11 +157,36 @@ class Cell:
         Reads a Cell from the boc ``bytes``.
         """
 
+    @staticmethod
+    def parse(text: str) -> Cell:
+        """
+        Parses a cell from its text dump: either the ``C("hex", C(...), ...)`` format
+        produced by ``str(cell)``, or the Fift ``x{ABCD_}`` format with references
+        indented below their parents one space per level and exotic cells marked with ``SPECIAL``.
+        """
+
+    @staticmethod
+    def read_file(path: str, lazy: bool = True) -> Cell:
+        """
+        Reads a Cell from the boc file at ``path``. In the ``lazy`` mode, the file is memory-mapped,
+        and cells are loaded on demand as the tree is traversed, so that large bocs (e.g. shard states)
+        can be explored without loading them into memory. The layout of all cells is checked when
+        the file is opened, malformed cells raise BocError. Hashes of a lazy cell are taken from
+        the file if stored there, otherwise they are computed from the raw data on the first request,
+        which visits its whole subtree once. Only the hashes of the visited cells are kept, so that
+        subtrees shared by several parents are hashed only once.
+        """
+
     def repr_hash(self) -> int:
         """
         Returns the representation hash of the cell.
         """
 
+    def hash(self, index: int) -> int:
+        """
+        Returns the hash of the cell at the level ``index``.
+        """
+
     def repr_depth(self) -> int:
         """
         Returns the representation depth of the cell.
@@ -45,6 +197,11 @@ class Cell:
         Returns level of the cell.
         """
 
+    def level_mask(self) -> int:
+        """
+        Returns the level mask of the cell.
+        """
+
     def depth(self, index: int) -> int:
         """
         Returns depth of the cell.
@@ -60,6 +217,35 @@ class Cell:
         Returns the unique cells count.
         """
 
+    @staticmethod
+    def diff(a: Cell, b: Cell, first: bool = False) -> list[CellDiff]:
+        """
+        Walks the trees ``a`` and ``b`` in parallel, skipping the subtrees with equal hashes,
+        and reports the cells whose data, reference count or type differ.
+        With ``first`` set, stops at the first such cell.
+        """
+
+    def dump(self, format: str = "playground") -> str:
+        """
+        Dumps the cell tree as text in one of the formats:
+        - ``"playground"``, the ``C("hex", C(...))`` format of ``str(cell)``,
+        - ``"fift"``, the ``x{ABCD_}`` format of Fift's ``csr.`` with references indented by one space,
+        - ``"tree"``, the Fift format with the bit and reference counts, depth and hash of every cell.
+        """
+
+class CellDiff:
+    """
+    Difference between two cells found by ``Cell.diff()``.
+    """
+    path: list[int]
+    """Reference indexes leading from the roots to the cells."""
+    bit_offset: Optional[int]
+    """Offset of the first differing bit, ``None`` if the bitstrings are equal."""
+    a: Cell
+    b: Cell
+
+    def __str__(self) -> str: ...
+
 class Slice:
     """
     A TVM cell slice, or slice for short, is a contiguous “sub-cell”
@@ -106,6 +292,95 @@ class Slice:
         Skips a number of bits.
         """
 
+    def peek_i(self, bits: int) -> int:
+        """
+        Reads a signed integer of bit length ``bits`` without advancing the data pointer (same as PLDIX).
+        """
+
+    def peek_u(self, bits: int) -> int:
+        """
+        Reads an unsigned integer of bit length ``bits`` without advancing the data pointer (same as PLDUX).
+        """
+
+    def peek_bits(self, bits: int) -> Slice:
+        """
+        Returns the first ``bits`` bits as a new slice without advancing the data pointer (same as PLDSLICEX).
+        """
+
+    def begins_with(self, prefix: Slice) -> bool:
+        """
+        Checks whether the data of the slice begins with the data of ``prefix`` (same as SDBEGINSXQ without skipping).
+        """
+
+    def try_skip_prefix(self, prefix: Slice) -> bool:
+        """
+        Skips ``prefix`` if the slice begins with it, returns whether it did (same as SDBEGINSXQ).
+        """
+
+    def cut_first(self, bits: int, refs: int = 0) -> Slice:
+        """
+        Returns the first ``bits`` bits and ``refs`` references as a new slice (same as SCUTFIRST).
+        """
+
+    def cut_last(self, bits: int, refs: int = 0) -> Slice:
+        """
+        Returns the last ``bits`` bits and ``refs`` references as a new slice (same as SCUTLAST).
+        """
+
+    def skip_last(self, bits: int, refs: int = 0):
+        """
+        Drops the last ``bits`` bits and ``refs`` references of the slice (same as SSKIPLAST).
+        """
+
+    def bytes_chain(self) -> bytes:
+        """
+        Reads the remaining bytes of the slice followed by the bytes of the chain of cells
+        (the snake format). A reference is followed as a chain link only when it is the only
+        reference of a cell with no room for another byte. The data and the link are consumed.
+        """
+
+    def string(self) -> str:
+        """
+        Reads a UTF-8 string stored with ``bytes_chain()``, e.g. the text of a comment after its zero op.
+        """
+
+    def address(self) -> Address:
+        """
+        Reads a ``MsgAddress`` of any kind.
+        """
+
+    def var_uint(self, len_bits: int) -> int:
+        """
+        Reads a ``VarUInteger``: a byte length of ``len_bits`` bits followed by the value.
+        """
+
+    def coins(self) -> int:
+        """
+        Reads an amount of ``Grams`` (same as ``var_uint(4)``, or LDGRAMS).
+        """
+
+    def maybe_ref(self) -> Optional[Cell]:
+        """
+        Reads a ``Maybe ^Cell``: a flag bit followed by a reference if the flag is set (same as LDOPTREF).
+        """
+
+    def either_ref(self) -> Slice:
+        """
+        Reads an ``Either X ^X``: returns the slice of the referenced cell if the flag bit is set,
+        otherwise the rest of this slice, which is consumed entirely.
+        """
+
+    def bits_and_refs(self) -> Tuple[int, int]:
+        """
+        Returns the numbers of the remaining bits and references (same as SBITREFS).
+        """
+
+    def split(self, bits: int, refs: int = 0) -> Tuple[Slice, Slice]:
+        """
+        Splits the slice into the first ``bits`` bits and ``refs`` references, and the rest (same as SPLIT).
+        The slice itself is left intact.
+        """
+
 class Builder:
     """
     A TVM cell builder, or builder for short, is an “incomplete”
@@ -157,6 +432,44 @@ class Builder:
         Appends Builder with ``cell``.
         """
 
+    def bytes_chain(self, data: bytes) -> Builder:
+        """
+        Writes as many bytes of ``data`` as fit into the builder, and the rest into a chain
+        of cells each linked by its only reference (the snake format). Continuing the chain
+        requires a builder without references, and no references may be added after it.
+        """
+
+    def string(self, text: str, snake: bool = True) -> Builder:
+        """
+        Writes the UTF-8 encoding of ``text``, with ``snake`` set it is split with ``bytes_chain()``.
+        """
+
+    def address(self, address: Address) -> Builder:
+        """
+        Writes ``address`` as a ``MsgAddress``.
+        """
+
+    def var_uint(self, len_bits: int, value: int) -> Builder:
+        """
+        Writes a ``VarUInteger``: a byte length of ``len_bits`` bits followed by the value.
+        """
+
+    def coins(self, value: int) -> Builder:
+        """
+        Writes an amount of ``Grams`` (same as ``var_uint(4, value)``, or STGRAMS).
+        """
+
+    def maybe_ref(self, cell: Optional[Cell]) -> Builder:
+        """
+        Writes a ``Maybe ^Cell``: a flag bit followed by a reference if ``cell`` is not None (same as STOPTREF).
+        """
+
+    def either_ref(self, cell: Cell, as_ref: Optional[bool] = None) -> Builder:
+        """
+        Writes an ``Either X ^X``: ``cell`` is stored inline or as a reference depending on ``as_ref``.
+        By default, the cell is stored inline if it fits into the builder.
+        """
+
     def fits(self, slice: Slice, extra_bits: int, extra_refs: int) -> bool:
         """
         Checks whether Builder can be appended with ``slice``, some extra data of the
@@ -222,6 +535,126 @@ class Dictionary:
         Deserializes Dictionary from ``slice`` with the ``bits`` key length.
         """
 
+class Boc:
+    """
+    Bag of cells serialization with any number of roots.
+
+    The ``write*`` methods accept the following options:
+    - ``index`` enables bag-of-cells index creation,
+    - ``crc`` includes the CRC32-C of all data into the serialization,
+    - ``ref_size`` and ``offset_size`` override the sizes (in bytes) of cell references and offsets,
+    - ``cache_bits`` marks the index entries of the cells referenced more than once; it implies ``index``.
+    """
+
+    @staticmethod
+    def read(bytes: bytes) -> list[Cell]:
+        """Reads the roots of the boc ``bytes``."""
+
+    @staticmethod
+    def write(roots: list[Cell], index: bool = False, crc: bool = False,
+              ref_size: Optional[int] = None, offset_size: Optional[int] = None,
+              cache_bits: bool = False) -> bytes:
+        """Writes ``roots`` to boc bytes."""
+
+    @staticmethod
+    def read_base64(text: str) -> list[Cell]:
+        """Reads the roots of the base64-encoded boc."""
+
+    @staticmethod
+    def write_base64(roots: list[Cell], index: bool = False, crc: bool = False,
+                     ref_size: Optional[int] = None, offset_size: Optional[int] = None,
+                     cache_bits: bool = False) -> str:
+        """Writes ``roots`` to a base64-encoded boc."""
+
+    @staticmethod
+    def read_hex(text: str) -> list[Cell]:
+        """Reads the roots of the hex-encoded boc."""
+
+    @staticmethod
+    def write_hex(roots: list[Cell], index: bool = False, crc: bool = False,
+                  ref_size: Optional[int] = None, offset_size: Optional[int] = None,
+                  cache_bits: bool = False) -> str:
+        """Writes ``roots`` to a hex-encoded boc."""
+
+    @staticmethod
+    def inspect(bytes: bytes, strict: bool = False) -> BocInfo:
+        """
+        Parses the header of the boc ``bytes`` and checks the layout of its cells.
+        With ``strict`` set, also reports the deviations from the canonical serialization:
+        oversized reference and offset fields, non-canonical cell data, CRC mismatches,
+        trailing bytes and duplicate cells.
+        """
+
+class BocInfo:
+    """
+    Header of a bag of cells as returned by ``Boc.inspect()``.
+    """
+    magic: int
+    flags: int
+    has_index: bool
+    has_crc: bool
+    has_cache_bits: bool
+    size_bytes: int
+    """Size of a cell reference in bytes."""
+    offset_bytes: int
+    """Size of a cell offset in bytes."""
+    cells: int
+    roots: list[int]
+    """Indexes of the root cells."""
+    absent: int
+    total_cells_size: int
+    issues: Optional[list[str]]
+    """Validation issues found in the strict mode, ``None`` otherwise."""
+
+class MerkleProof:
+    """
+    Merkle proof of a cell tree with some subtrees replaced by pruned branches.
+    """
+    cell: Cell
+    root_hash: int
+
+    def __init__(self, cell: Cell) -> None: ...
+
+    @staticmethod
+    def make(root: Cell, visited: Union[Iterable[int], Callable[[Cell], bool]]) -> MerkleProof:
+        """
+        Same as ``root.make_merkle_proof(visited)``.
+        """
+
+    def verify(self, root_hash: int) -> bool:
+        """
+        Checks whether the proof is made for the tree with the ``root_hash`` repr hash.
+        """
+
+    def virtualize(self) -> Cell:
+        """
+        Returns the virtual root of the proven tree. Its hash(0) equals the hash of the original root.
+        """
+
+class MerkleUpdate:
+    """
+    Merkle update transforming a cell tree with the ``old_hash`` repr hash into
+    the tree with the ``new_hash`` one.
+    """
+    cell: Cell
+    old_hash: int
+    new_hash: int
+
+    def __init__(self, cell: Cell) -> None: ...
+
+    @staticmethod
+    def create(old: Cell, new: Cell) -> MerkleUpdate:
+        """
+        Creates the minimal update from ``old`` to ``new``: the subtrees of ``old`` containing
+        no cells reused by ``new`` are pruned, as are the subtrees of ``new`` taken from ``old``.
+        """
+
+    def apply(self, old: Cell) -> Cell:
+        """
+        Applies the update to ``old`` and returns the new tree. Both the hash of ``old``
+        and the hash of the result are checked against the ones stored in the update.
+        """
+
 class NaN:
     """
     NaN is a special case of the TVM Integer type.
@@ -332,18 +765,208 @@ class VmState:
 
     def __init__(self, cc: Continuation, regs: SaveList, gas: Gas) -> None: ...
 
+class TraceStep:
+    """A single step of TVM execution captured with ``trace = "collect"``."""
+    step: int
+    insn: str
+    cell_hash: int
+    offset: int
+    gas_cmd: int
+    gas_used: int
+    exception: bool
+    stack: list[object]
+
+class GasProfile:
+    """
+    Gas and step counts aggregated over the executed instructions, collected with ``profile = True``.
+    """
+    total_gas: int
+    total_steps: int
+
+    def by_mnemonic(self) -> dict[str, Tuple[int, int]]:
+        """Returns (gas, steps) pairs keyed by instruction mnemonic."""
+
+    def by_cell(self) -> dict[int, Tuple[int, int]]:
+        """Returns (gas, steps) pairs keyed by code cell repr hash."""
+
+    def by_line(self, debug_map: dict[Tuple[int, int], Tuple[str, int]]) -> dict[Tuple[str, int], Tuple[int, int]]:
+        """
+        Returns (gas, steps) pairs keyed by (filename, line) of the assembler source,
+        using ``debug_map`` obtained from assemble_debug().
+        """
+
+    def folded(self) -> str:
+        """
+        Exports the profile in the folded stacks format accepted by flamegraph tools.
+        Every line has a ``<code cell hash>;<mnemonic> <gas>`` form.
+        """
+
+class Coverage:
+    """
+    Hit counts of executed instructions keyed by (code cell repr hash, bit offset).
+    The same Coverage object may be passed to several runs to accumulate the counts.
+    """
+    def __init__(self) -> None: ...
+
+    def merge(self, other: Coverage) -> Coverage:
+        """Adds the hit counts of ``other`` to this coverage."""
+
+    def hits(self) -> dict[Tuple[int, int], int]:
+        """Returns hit counts keyed by (code cell repr hash, bit offset)."""
+
+    def count(self, cell_hash: int, offset: int) -> int:
+        """Returns the hit count of the instruction at ``offset`` of the ``cell_hash`` code cell."""
+
+    def lcov(self, debug_map: dict[Tuple[int, int], Tuple[str, int]]) -> str:
+        """
+        Emits the coverage in the lcov tracefile format. The ``debug_map`` maps every
+        instruction position (code cell repr hash, bit offset) to a (filename, line) pair.
+        """
+
 class VmResult:
-    """Result of TVM execution."""
+    """
+    Result of TVM execution. The ``trace`` list is only filled in when the VM
+    is run with ``trace = "collect"``. The ``interrupted`` flag is set when
+    the execution was stopped by a hook or a breakpoint before its completion,
+    and ``breakpoint`` holds the index of the breakpoint that was hit.
+    """
     state: VmState
     exit_code: int
     exception_value: object
+    trace: Optional[list[TraceStep]]
+    interrupted: bool
+    breakpoint: Optional[int]
+    profile: Optional[GasProfile]
+
+class Vm:
+    """
+    Step-by-step TVM executor. Unlike runvm_generic(), the VM keeps its state between
+    calls, so cc, the stack, registers and gas can be inspected after every instruction.
+
+    Example:
+    ```
+        vm = Vm(VmState(cc, SaveList(), Gas(1000000, 0, 1000000, 10)))
+        while vm.step() is None:
+            print(vm.steps, vm.stack)
+    ```
+    """
+    finished: bool
+    exit_code: Optional[int]
+    exception_value: object
+    cc: Continuation
+    stack: list[object]
+    regs: SaveList
+    gas: Gas
+    steps: int
+
+    def __init__(self, state: VmState, capabilities: int = 0, libs: list = []) -> None: ...
+
+    def step(self) -> Optional[int]:
+        """
+        Executes a single instruction. Returns the exit code if the execution is finished,
+        and None otherwise.
+        """
+
+    def run(self, max_steps: Optional[int] = None) -> Optional[int]:
+        """
+        Executes at most ``max_steps`` instructions, or runs to completion if ``max_steps`` is None.
+        Returns the exit code if the execution is finished, and None otherwise.
+        """
+
+    def run_until(self, predicate: Callable[[Vm], bool]) -> Optional[int]:
+        """
+        Executes instructions until ``predicate`` called with the VM after each step returns True,
+        or the execution is finished. Returns the exit code if the execution is finished, and None otherwise.
+        """
+
+    def ctrl(self, index: int) -> object:
+        """Gets the value of the control register c``index``."""
+
+    def state(self) -> VmState:
+        """Returns a snapshot of the current VM state."""
+
+    def result(self) -> VmResult:
+        """Returns the result of the finished execution."""
+
+def runvm_generic(
+    state: VmState,
+    capabilities: int = 0,
+    trace: Union[bool, str] = False,
+    libs: list = [],
+    hook: Optional[Callable[[TraceStep], Optional[int]]] = None,
+    breakpoints: list[Union[Tuple[int, int], str]] = [],
+    profile: bool = False,
+    coverage: Optional[Coverage] = None
+) -> VmResult:
+    """
+    Generic version of the runvm() function.
+
+    The ``trace`` parameter accepts ``True`` or ``"print"`` to print every step to stdout,
+    and ``"collect"`` to return the steps in VmResult.trace.
 
-def runvm_generic(state: VmState, capabilities: int = 0, trace: bool = False, libs: list = []) -> VmResult:
-    """Generic version of the runvm() function."""
+    The ``hook`` callable is invoked with a TraceStep after every executed instruction.
+    Returning an integer from the hook stops the execution with that integer as the exit code,
+    and raising an exception aborts the execution and propagates the exception.
+
+    Each of ``breakpoints`` is either a (code cell repr hash, bit offset) tuple or an instruction
+    mnemonic. The execution pauses right before a matching instruction, and the resulting
+    VmResult.state can be passed to runvm_generic() again to resume. Breakpoints are not checked
+    before the first instruction of a run.
+
+    With ``profile = True``, gas usage is aggregated into VmResult.profile.
+    Executed instructions are counted into ``coverage`` if it is given.
+    """
+
+class AssemblerError(PlaygroundError):
+    """
+    Raised when assembling fails. Describes the first error by ``line``, ``column``,
+    the offending ``token`` (if any), and ``message``. The ``errors`` list holds
+    (line, column, token, message) tuples of all the errors found.
+    """
+    line: int
+    column: int
+    token: Optional[str]
+    message: str
+    errors: list[Tuple[int, int, Optional[str], str]]
 
 def assemble(code: str) -> Cell:
     """
     Translates the ``code`` string in assembler language to a Cell of TVM bytecode.
+    Raises AssemblerError on failure.
+    """
+
+def assemble_debug(code: str, filename: str = "<code>") -> Tuple[Cell, dict[Tuple[int, int], Tuple[str, int]]]:
+    """
+    Translates the ``code`` string in assembler language to a Cell of TVM bytecode,
+    and returns it along with a debug map. The debug map binds every instruction position,
+    (code cell repr hash, bit offset), to the (filename, line) pair of its source.
+    The map fits TraceStep.cell_hash/offset, Coverage.lcov() and GasProfile.by_line().
+    """
+
+class Instruction:
+    """
+    A disassembled TVM instruction located at ``offset`` of the code cell with ``cell_hash``.
+    It occupies ``length`` bits and ``refs`` references of the cell, and nested code blocks
+    (e.g. bodies of PUSHCONT or CALLREF) are listed in ``blocks``.
+    """
+    mnemonic: str
+    operands: list[str]
+    cell_hash: int
+    offset: int
+    length: int
+    refs: int
+    blocks: list[list[Instruction]]
+
+def disassemble(code: Union[Cell, Slice]) -> str:
+    """
+    Translates TVM bytecode to a text in assembler language. Nested code blocks
+    stored in references are disassembled as well, so that
+    ``assemble(disassemble(cell)) == cell`` for canonically encoded code.
+    """
+
+def disassemble_instructions(code: Union[Cell, Slice]) -> list[Instruction]:
+    """
+    Disassembles TVM bytecode into a list of Instruction objects.
     """
 
 def ed25519_new_keypair() -> Tuple[bytes, bytes]:
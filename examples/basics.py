@@ -3,6 +3,8 @@ from ever_playground import Builder as B
 from ever_playground import Slice as S
 from ever_playground import Dictionary as D
 from ever_playground import parse_smc_addr
+from ever_playground import Boc, MerkleUpdate
+from ever_playground import PlaygroundError, CellOverflowError, CellUnderflowError, BocError, InvalidBitstringError
 
 def expect(expected, v):
     if not expected == v:
@@ -76,3 +78,241 @@ expect(dict_cell1, dict_cell2)
 
 #open("test.boc", "wb").write(dict_cell.write())
 #cell = C.read(open("test.boc", "rb").read())
+
+def expect_raises(error, f):
+    try:
+        f()
+    except error:
+        return
+    raise Exception("{} is not raised".format(error.__name__))
+
+expect_raises(CellOverflowError, lambda: B().i(256, 0).i(256, 0).i(256, 0).i(256, 0))
+expect_raises(CellUnderflowError, lambda: S(C("ab")).u(16))
+expect_raises(BocError, lambda: C.read(b"garbage"))
+expect_raises(InvalidBitstringError, lambda: B().x("xyz"))
+expect_raises(InvalidBitstringError, lambda: B().ib("012"))
+expect_raises(CellOverflowError, lambda: C("", C(""), C(""), C(""), C(""), C("")))
+expect_raises(CellOverflowError, lambda: C.parse('C("", C(""), C(""), C(""), C(""), C(""))'))
+
+leaf = C("deadbeef")
+pruned = C.pruned_branch(leaf)
+expect("pruned_branch", pruned.cell_type())
+deep = C.pruned_branch(leaf, 1)
+s = S(C.pruned_branch(deep, 2))
+expect((1, 0b110), (s.u(8), s.u(8)))
+expect((deep.hash(0), deep.hash(2)), (s.u(256), s.u(256)))
+expect((deep.depth(0), deep.depth(2)), (s.u(16), s.u(16)))
+expect_raises(PlaygroundError, lambda: C.pruned_branch(deep, 0))
+expect(True, pruned.is_exotic())
+expect(1, pruned.level())
+expect(leaf.repr_hash(), pruned.hash(0))
+
+proof = C.merkle_proof(C("", pruned))
+expect("merkle_proof", proof.cell_type())
+expect(0, proof.level())
+
+lib = C.library(leaf.repr_hash())
+expect(C.exotic("02{:064x}".format(leaf.repr_hash())), lib)
+
+update = C.merkle_update(leaf, C("cafe"))
+expect("merkle_update", update.cell_type())
+expect(C("cafe"), update.reference(1))
+
+left = C("aa", C("a1"))
+right = C("bb", C("b1"))
+root = C("cc", left, right)
+proof = root.make_merkle_proof({left.repr_hash()})
+expect(True, proof.verify(root.repr_hash()))
+expect(False, proof.verify(left.repr_hash()))
+virtual = proof.virtualize()
+expect(root.repr_hash(), virtual.hash(0))
+expect(left.repr_hash(), virtual.reference(0).hash(0))
+expect("pruned_branch", virtual.reference(0).reference(0).cell_type())
+expect("pruned_branch", virtual.reference(1).cell_type())
+expect(proof.cell, root.make_merkle_proof(lambda c: c == left).cell)
+
+old_c4 = C("01", C("aa", C("a1")), C("bb"))
+new_c4 = C("02", C("aa", C("a1")), C("bc"))
+update = MerkleUpdate.create(old_c4, new_c4)
+expect(old_c4.repr_hash(), update.old_hash)
+expect(new_c4.repr_hash(), update.new_hash)
+expect(new_c4, update.apply(old_c4))
+expect("pruned_branch", update.cell.reference(1).reference(0).cell_type())
+wipe = MerkleUpdate.create(old_c4, C(""))
+expect("pruned_branch", wipe.cell.reference(0).reference(0).cell_type())
+expect("pruned_branch", wipe.cell.reference(0).reference(1).cell_type())
+expect(C(""), wipe.apply(old_c4))
+
+roots = [C("01", C("aa")), C("02"), C("03", C("aa"))]
+expect(roots, Boc.read(Boc.write(roots)))
+expect(roots, Boc.read(Boc.write(roots, index = True, crc = True)))
+expect(roots, Boc.read_base64(Boc.write_base64(roots)))
+expect(roots, Boc.read_hex(Boc.write_hex(roots)))
+expect([dict_cell1], Boc.read(dict_bytes))
+
+info = Boc.inspect(Boc.write(roots, index = True, crc = True), strict = True)
+expect(0xb5ee9c72, info.magic)
+expect((True, True, 1, 1), (info.has_index, info.has_crc, info.size_bytes, info.offset_bytes))
+expect((4, 3, 0), (info.cells, len(info.roots), info.absent))
+expect([], info.issues)
+cached = Boc.write(roots, crc = True, cache_bits = True)
+expect(roots, Boc.read(cached))
+info = Boc.inspect(cached, strict = True)
+expect((True, True, True), (info.has_index, info.has_crc, info.has_cache_bits))
+expect([], info.issues)
+expect(None, Boc.inspect(Boc.write(roots)).issues)
+loose = bytearray(Boc.write(roots, crc = True, ref_size = 2))
+loose[-1] ^= 1
+issues = Boc.inspect(bytes(loose), strict = True).issues
+expect(2, len(issues))
+expect(True, issues[0].startswith("size_bytes 2"))
+expect(True, issues[1].startswith("crc mismatch"))
+duplicated = bytes.fromhex("b5ee9c720101030100" + "0a00" + "020001020002aa0002aa")
+expect(["cell 2 duplicates cell 1"], Boc.inspect(duplicated, strict = True).issues)
+
+fift_bytes = bytes.fromhex("b5ee9c72010103010" + "00b00" + "0202010102" + "0002aa" + "0002bb")
+expect(fift_bytes, C("01", C("aa"), C("bb")).write(0, fift = True))
+
+import os
+import tempfile
+
+big = C("01", C("aa", C("a1"), C("a2")), C("bb", C("aa", C("a1"), C("a2"))))
+for flags in [0, 1, 2, 3]:
+    expect(big, C.read(big.write(flags, fift = True)))
+    expect([], Boc.inspect(big.write(flags, fift = True), strict = True).issues)
+for flags in [0, 1, 3]:
+    with tempfile.NamedTemporaryFile(suffix = ".boc", delete = False) as file:
+        file.write(big.write(flags))
+    lazy = C.read_file(file.name)
+    expect(S(C("a2")), S(lazy.reference(0).reference(1)))
+    expect(big.repr_hash(), lazy.repr_hash())
+    expect(big, C.read_file(file.name, lazy = False))
+    os.remove(file.name)
+
+def read_lazy(data: bytes) -> C:
+    with tempfile.NamedTemporaryFile(suffix = ".boc", delete = False) as file:
+        file.write(data)
+    try:
+        return C.read_file(file.name)
+    finally:
+        os.remove(file.name)
+
+expect(update.cell.repr_hash(), read_lazy(update.cell.write(0)).repr_hash())
+expect(pruned.repr_hash(), read_lazy(pruned.write(0)).repr_hash())
+five_refs = bytes.fromhex("b5ee9c72010106010011" + "00" + "05000102030405" + "0000" * 5)
+expect_raises(BocError, lambda: read_lazy(five_refs))
+expect(True, "cell 0 has 5 references" in Boc.inspect(five_refs, strict = True).issues)
+huge = bytes.fromhex("b5ee9c72010801010" + "0ffffffffffffffff" + "00")
+expect_raises(BocError, lambda: Boc.inspect(huge))
+short_pruned = bytes.fromhex("b5ee9c72010101010004" + "00" + "28040101")
+expect_raises(BocError, lambda: read_lazy(short_pruned))
+
+expect(big, C.parse(str(big)))
+expect(big, C.parse("""
+x{01}
+ x{AA}
+  x{A1}
+  x{A2}
+ x{BB}
+  x{AA}
+   x{A1}
+   x{A2}
+"""))
+expect(C("6_", C("")), C.parse("b{01}\n b{}"))
+expect_raises(PlaygroundError, lambda: C.parse("x{01}\n  x{02}"))
+expect_raises(PlaygroundError, lambda: C.parse("C(\"01\""))
+
+expect("x{01}\n x{AA}\n  x{A1}\n  x{A2}\n x{BB}\n  x{AA}\n   x{A1}\n   x{A2}\n", big.dump("fift"))
+expect(big, C.parse(big.dump("fift")))
+expect(str(big), big.dump())
+expect(True, big.dump("tree").startswith("x{01} bits=8 refs=2 depth=3 hash={:064x}".format(big.repr_hash())))
+expect(True, pruned.dump("fift").startswith("SPECIAL x{01"))
+expect(pruned, C.parse(pruned.dump("fift")))
+
+changed = C("01", C("aa", C("a1"), C("a3")), C("bb", C("ab", C("a1"), C("a2")), C("")))
+diffs = C.diff(big, changed)
+expect([[0, 1], [1], [1, 0]], [d.path for d in diffs])
+expect([7, None, 7], [d.bit_offset for d in diffs])
+expect("[1]: refs 1 != 2", str(diffs[1]))
+expect(1, len(C.diff(big, changed, first = True)))
+expect([], C.diff(big, big))
+
+s = S(C("12345678", C("aa"), C("bb")))
+expect(0x12, s.peek_u(8))
+expect(0x1, s.peek_i(4))
+expect(S(C("123")), s.peek_bits(12))
+expect((32, 2), s.bits_and_refs())
+expect(True, s.begins_with(S(C("12"))))
+expect(False, s.try_skip_prefix(S(C("13"))))
+expect(True, s.try_skip_prefix(S(C("12"))))
+expect(S(C("34", C("aa"))), s.cut_first(8, 1))
+expect(S(C("78", C("bb"))), s.cut_last(8, 1))
+head, tail = s.split(16, 1)
+expect(S(C("3456", C("aa"))), head)
+expect(S(C("78", C("bb"))), tail)
+s.skip_last(8, 1)
+expect(S(C("3456", C("aa"))), s)
+expect_raises(CellUnderflowError, lambda: s.cut_last(17))
+
+b = B().coins(0).coins(1000000000).var_uint(5, 2 ** 100).maybe_ref(None).maybe_ref(C("aa"))
+b.either_ref(C("bb")).either_ref(C("cc"), as_ref = True)
+expect(B().i(4, 0).i(4, 4).i(32, 1000000000).i(5, 13).i(104, 2 ** 100).ib("01").r(C("aa")).ib("0").x("bb").ib("1").r(C("cc")).finalize(), b.finalize())
+s = b.slice()
+expect((0, 1000000000, 2 ** 100), (s.coins(), s.coins(), s.var_uint(5)))
+expect((None, C("aa")), (s.maybe_ref(), s.maybe_ref()))
+inline = s.either_ref()
+expect(True, s.is_empty())
+expect(0xbb, inline.u(8))
+expect(S(C("cc")), inline.either_ref())
+expect_raises(PlaygroundError, lambda: B().coins(2 ** 120))
+
+from ever_playground import Address
+
+std = Address("0:" + "ab" * 32)
+expect(("std", 0, int("ab" * 32, 16), None), (std.kind, std.workchain, std.address, std.anycast))
+expect(std, Address.std(0, int("ab" * 32, 16)))
+expect_raises(PlaygroundError, lambda: Address.std(0, 1 << 256))
+expect(B().ib("100").i(8, 0).i(256, std.address).finalize(), B().address(std).finalize())
+anycast = Address.std(-1, 1, S(C("a_")))
+var = Address("5:abc")
+ext = Address(":ab_")
+none = Address("")
+for addr in [std, anycast, var, ext, none]:
+    expect(addr, B().address(addr).slice().address())
+    expect(addr, Address(str(addr)))
+expect(("var", 5, 12), (var.kind, var.workchain, var.bits.remaining_bits()))
+expect(("extern", None, "ab_"), (ext.kind, ext.workchain, str(ext)[1:]))
+expect(("none", ""), (none.kind, str(none)))
+expect(C("a_"), B().s(anycast.anycast).finalize())
+expect(B().ib("00").finalize(), B().address(none).finalize())
+
+friendly = "EQDKbjIcfM6ezt8KjKJJLshZJJSqX7XOA4ff-W72r5gqPrHF"
+raw = Address("0:ca6e321c7cce9ecedf0a8ca2492ec8592494aa5fb5ce0387dff96ef6af982a3e")
+expect((raw, True, False), Address.from_friendly(friendly))
+expect(raw, Address(friendly))
+expect(friendly, raw.to_friendly())
+expect(raw, Address(raw.to_friendly(bounceable = False, testnet = True, url_safe = False)))
+expect((raw, False, True), Address.from_friendly(raw.to_friendly(bounceable = False, testnet = True)))
+expect((0, raw.address), parse_smc_addr(friendly))
+expect((0, 255), parse_smc_addr("0:ff"))
+expect_raises(PlaygroundError, lambda: Address.from_friendly(friendly[:-1] + "G"))
+expect_raises(PlaygroundError, lambda: anycast.to_friendly())
+
+text = "Comment " * 50
+comment = B().i(32, 0).string(text).finalize()
+expect(32 + 123 * 8, S(comment).remaining_bits())
+expect(127 * 8, S(comment.reference(0)).remaining_bits())
+expect(23 * 8, S(comment.reference(0).reference(0).reference(0)).remaining_bits())
+s = S(comment)
+expect(0, s.u(32))
+expect(text, s.string())
+expect((0, 0), s.bits_and_refs())
+s = B().r(C("aa")).string("hi").slice()
+expect("hi", s.string())
+expect(C("aa"), s.r())
+s = B().r(C("aa")).bytes_chain(bytes(range(100))).slice()
+expect(bytes(range(100)), s.bytes_chain())
+expect((0, 1), s.bits_and_refs())
+expect_raises(CellOverflowError, lambda: B().r(C("aa")).bytes_chain(bytes(range(200))))
+expect_raises(CellOverflowError, lambda: B().r(C("aa")).bytes_chain(bytes(range(127))))
+expect_raises(CellOverflowError, lambda: B().string(text, snake = False))
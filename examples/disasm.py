@@ -0,0 +1,46 @@
+from ever_playground import *
+
+def expect(expected, v):
+    if not expected == v:
+        raise Exception("{} != {}".format(expected, v))
+
+codes = [
+    "ADD",
+    "PUSHINT 1 PUSHINT 2 PUSHCONT { INC } IF",
+    """
+    DUP
+    PUSHINT 4444
+    EQUAL
+    IFJMPREF {
+        NOP NOP
+        CALL 13
+    }
+    THROW 1
+    """,
+    "CALLREF { PUSHINT 13 } PUSHCONT { PUSHCONT { DEC } CALLX } REPEAT",
+]
+
+for text in codes:
+    code = assemble(text)
+    expect(code, assemble(disassemble(code)))
+
+# bytecode of a counter contract encoded by hand as Fift does it, not produced by the assembler:
+# c4 PUSH CTOS 32 LDU DROP ADD NEWC 32 STU ENDC c4 POP
+fift_code = Cell("ED44D0D31F30A0C8CB1FC9ED54")
+expect(fift_code, assemble(disassemble(fift_code)))
+expect(9, len(disassemble_instructions(fift_code)))
+
+# long form of PUSHINT 1, which the assembler would encode in 8 bits
+insns = disassemble_instructions(Cell("800171"))
+expect(["PUSHINT", "PUSHINT"], [i.mnemonic for i in insns])
+expect([(0, 16), (16, 8)], [(i.offset, i.length) for i in insns])
+
+code = assemble("PUSHINT 1 CALLREF { PUSHINT 2 MUL } INC")
+insns = disassemble_instructions(code)
+expect(["PUSHINT", "CALLREF", "INC"], [i.mnemonic for i in insns])
+expect(["1"], insns[0].operands)
+expect([0, 8, 24], [i.offset for i in insns])
+expect(1, insns[1].refs)
+callee = insns[1].blocks[0]
+expect(["PUSHINT", "MUL"], [i.mnemonic for i in callee])
+expect(code.reference(0).repr_hash(), callee[0].cell_hash)
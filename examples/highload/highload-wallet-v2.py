@@ -1,7 +1,7 @@
 import sys
 import time
 import argparse
-from ever_playground import Builder, Cell, Currency, Dictionary, Slice, \
+from ever_playground import Address, Builder, Cell, Currency, Dictionary, Slice, \
     parse_smc_addr, load_address, load_keypair, ed25519_sign
 
 def abort(message: str):
@@ -64,7 +64,7 @@ def append_msg_body(b: Builder, body: Cell):
         b.i(1, 1).r(body)
 
 def create_int_msg(body: Cell, bounce: bool, wc: int, addr: int, ng: Currency) -> Cell:
-    b = Builder().ib("01").i(1, bounce).ib("000100").i(8, wc).i(256, addr) \
+    b = Builder().ib("01").i(1, bounce).ib("0").address(Address.none()).address(Address.std(wc, addr)) \
         .b(ng.serialize()).i(9 + 64 + 32 + 1, 0)
     append_msg_body(b, body)
     return b.finalize()
@@ -101,10 +101,10 @@ signing_message = Builder().i(32, subwallet_id).i(64, query_id).s(Slice(orders_c
 print(f"signing message: {signing_message}")
 
 signature = ed25519_sign(signing_message.repr_hash().to_bytes(32, "big"), wallet_pk)
-ext = Builder().ib("1000100").i(8, wallet_wc).i(256, wallet_addr).b(Currency(0).serialize()) \
+ext = Builder().ib("10").address(Address.none()).address(Address.std(wallet_wc, wallet_addr)).b(Currency(0).serialize()) \
     .ib("00").y(signature).s(Slice(signing_message)).finalize()
 print(f"resulting external message: {ext}")
-ext_bytes = ext.write(2)
+ext_bytes = ext.write(2, fift = True)
 print(f"{ext_bytes.hex()}")
 print(f"query_id is {query_id} = 0x{query_id:x}")
 open(savefile + ".boc", "wb").write(ext_bytes)
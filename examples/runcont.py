@@ -30,3 +30,33 @@ program.stack.append(0)
 r = runcont(program)
 assert(r.exit_code == 500)
 assert(r.state.cc.stack == [5])
+
+def state_of(code: Slice, stack: list) -> VmState:
+    cc = Continuation(code = code, stack = stack)
+    return VmState(cc, SaveList(), Gas(1_000_000, 10_000, 1_000_000, 10))
+
+code = assemble("""
+    PUSHINT 1
+    CALLREF {
+        PUSHINT 2
+        MUL
+    }
+    INC
+""")
+r = runvm_generic(state_of(Slice(code), []), breakpoints = ["MUL"])
+assert(r.interrupted and r.breakpoint == 0)
+assert(r.state.cc.stack == [1, 2])
+
+# resuming with the same breakpoints runs past the instruction it is paused at
+paused = r.state
+r = runvm_generic(paused, breakpoints = ["MUL"])
+assert(r.exit_code == 0 and not r.interrupted)
+assert(r.state.cc.stack == [3])
+
+r = runvm_generic(paused, breakpoints = [(code.repr_hash(), 24)])
+assert(r.breakpoint == 0)
+assert(r.state.cc.stack == [2])
+
+r = runvm_generic(r.state)
+assert(r.exit_code == 0 and not r.interrupted)
+assert(r.state.cc.stack == [3])
@@ -3,7 +3,7 @@ import time
 from ever_playground import Cell as C
 from ever_playground import Slice as S
 from ever_playground import ExceptionCode
-from ever_playground import StateInit, runvm, assemble
+from ever_playground import StateInit, Coverage, AssemblerError, runvm, assemble, assemble_debug
 
 def expect(expected, v):
     if not expected == v:
@@ -38,6 +38,48 @@ expect(100, res.exit_code)
 expect(C(""), res.exception_value)
 expect([], res.state.cc.stack)
 
+res = runvm(S(assemble("PUSHINT 2 PUSHINT 3 ADD")), [], trace = "collect")
+expect(["PUSHINT 2", "PUSHINT 3", "ADD"], [step.insn for step in res.trace[:3]])
+expect([5], res.trace[2].stack)
+
+adds = []
+def count_adds(step):
+    if step.insn == "ADD":
+        adds.append(step.step)
+    if len(adds) == 2:
+        return 77
+res = runvm(S(assemble("ADD ADD ADD")), [1, 2, 3, 4], hook = count_adds)
+expect(77, res.exit_code)
+expect(True, res.interrupted)
+expect([1, 9], res.state.cc.stack)
+
+res = runvm(S(assemble("INC INC DEC")), [0], profile = True)
+expect(2, res.profile.by_mnemonic()["INC"][1])
+expect(1, res.profile.by_mnemonic()["DEC"][1])
+
+cov = Coverage()
+branchy = assemble("PUSHCONT { INC } IF")
+runvm(S(branchy), [0, 1], coverage = cov)
+runvm(S(branchy), [0, 0], coverage = cov)
+expect(2, cov.count(branchy.repr_hash(), 0)) # PUSHCONT
+expect(1, cov.count(branchy.repr_hash(), 8)) # INC
+expect(2, cov.count(branchy.repr_hash(), 16)) # IF
+
+code, debug_map = assemble_debug("PUSHINT 1\nPUSHINT 2\nADD", "add.code")
+expect(("add.code", 3), debug_map[(code.repr_hash(), 16)])
+cov = Coverage()
+res = runvm(S(code), [], coverage = cov, profile = True)
+expect("TN:\nSF:add.code\nDA:1,1\nDA:2,1\nDA:3,1\nLF:3\nLH:3\nend_of_record\n", cov.lcov(debug_map))
+expect(1, res.profile.by_line(debug_map)[("add.code", 3)][1])
+
+try:
+    assemble("PUSHINT 1\nFOO\nADD\nBAR 2")
+    raise Exception("assembling should fail")
+except AssemblerError as e:
+    expect(2, e.line)
+    expect("FOO", e.token)
+    expect([2, 4], [line for line, _, _, _ in e.errors])
+
 ctrls_ex = assemble("PUSHCTR c4")
 abc = C("abc")
 res = runvm(S(ctrls_ex), [], c4 = abc)
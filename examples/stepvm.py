@@ -0,0 +1,29 @@
+from ever_playground import *
+
+def expect(expected, v):
+    if not expected == v:
+        raise Exception("{} != {}".format(expected, v))
+
+def make_vm(text: str, stack: list) -> Vm:
+    cc = Continuation(code = Slice(assemble(text)), stack = stack)
+    return Vm(VmState(cc, SaveList(), Gas(1_000_000, 10_000, 1_000_000, 10)))
+
+vm = make_vm("ADD PUSHINT 3 MUL", [10, 20])
+expect(None, vm.step())
+expect([30], vm.stack)
+expect(1, vm.steps)
+expect(None, vm.step())
+expect([30, 3], vm.stack)
+expect(0, vm.run())
+expect(True, vm.finished)
+expect([90], vm.result().state.cc.stack)
+
+vm = make_vm("INC INC INC INC", [0])
+expect(None, vm.run(max_steps = 2))
+expect([2], vm.stack)
+expect(None, vm.run_until(lambda vm: vm.stack == [3]))
+expect(3, vm.steps)
+
+vm = make_vm("THROW 100", [])
+expect(100, vm.run())
+expect(0, vm.exception_value)
@@ -2,7 +2,7 @@
 # "TonUtil.fif" include
 
 import sys
-from ever_playground import Builder as B, Slice as S, Currency, parse_smc_addr, parse_load_address
+from ever_playground import Address, Builder as B, Slice as S, Currency, parse_smc_addr, parse_load_address
 
 # { ."usage: " @' $0 type ." <dest-addr> <seqno> <amount> [<savefile>]" cr
 #   ."Creates a request to TestGiver and saves it into <savefile>.boc" cr
@@ -51,7 +51,7 @@ print(f"Requesting {amount} to account {dest_address} seqno {hex(seqno)} bounce
 # <b seqno 32 u, 1 8 u, swap ref, b>
 # dup ."enveloping message: " <s csr. cr
 hint = bytes("GIFT", "utf8")
-c1 = B().ib("01").i(1, bounce).ib("000100").i(8, dest_wc).i(256, dest_addr) \
+c1 = B().ib("01").i(1, bounce).ib("0").address(Address.none()).address(Address.std(dest_wc, dest_addr)) \
     .b(amount.serialize()).i(9 + 64 + 32 + 1 + 1, 0).i(32, 0).y(hint) \
     .finalize()
 message = B().i(32, seqno).i(8, 1).r(c1).finalize()
@@ -63,7 +63,7 @@ print(f"enveloping message: {message}")
 # 2 boc+>B dup Bx. cr
 # savefile +".boc" tuck B>file
 # ."(Saved to file " type .")" cr
-ext_message = B().ib("1000100").i(8, giver_wc).i(256, giver_addr) \
+ext_message = B().ib("10").address(Address.none()).address(Address.std(giver_wc, giver_addr)) \
     .b(Currency(0).serialize()).ib("00").s(S(message)).finalize()
 print(f"resulting external message: {ext_message}")
 ext_message_bytes = ext_message.write(2) # include crc32
@@ -4,8 +4,6 @@ import filecmp
 import subprocess
 import os
 
-from ever_playground import Cell
-
 ton_src = "/e/ton"
 ton_build = ton_src + "/build"
 fift = [ton_build + "/crypto/fift", "-I", ton_src + "/crypto/fift/lib", "-s"]
@@ -81,13 +79,7 @@ def test_highload_wallet_v2():
     run(["python3", script + ".py", "sample", "27172", "order-list-py", "--savefile", py_output])
     run(fift + [script + ".fif", "sample", "27172", "order-list-fift", fift_output])
 
-    # boc serialization seems different, compare deser-ed cells instead
-    # assert_identical_files(py_output + ".boc", fift_output + ".boc")
-    p = Cell.read(open(py_output + ".boc", "rb").read())
-    f = Cell.read(open(fift_output + ".boc", "rb").read())
-    remove(py_output + ".boc")
-    remove(fift_output + ".boc")
-    assert(p == f)
+    assert_identical_files(py_output + ".boc", fift_output + ".boc")
 
     os.chdir(curdir)
 
@@ -100,10 +92,18 @@ def run_runcont():
 def run_libraries():
     run(["python3", "examples/libraries.py"])
 
+def run_stepvm():
+    run(["python3", "examples/stepvm.py"])
+
+def run_disasm():
+    run(["python3", "examples/disasm.py"])
+
 run_basics()
 run_runvm()
 run_runcont()
 run_libraries()
+run_stepvm()
+run_disasm()
 test_recover_stake()
 test_testgiver()
 test_validator_elect_req()
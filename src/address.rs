@@ -0,0 +1,268 @@
+use crate::{err, PySlice, utils::{runtime_err, unsigned_int_deserialize, unsigned_int_serialize}};
+use num_bigint::{BigInt, BigUint};
+use pyo3::{prelude::*, basic::CompareOp};
+use ton_block::{AnycastInfo, Deserializable, MsgAddressExt, MsgAddressInt, Serializable};
+use ton_types::{BuilderData, SliceData};
+
+#[derive(Clone, PartialEq)]
+pub(crate) enum Address {
+    Ext(MsgAddressExt),
+    Int(MsgAddressInt),
+}
+
+impl Address {
+    pub(crate) fn read(slice: &mut SliceData) -> PyResult<Self> {
+        // addr_none$00 and addr_extern$01 are external, addr_std$10 and addr_var$11 are internal
+        let internal = slice.clone().get_next_bit().map_err(runtime_err)?;
+        if internal {
+            MsgAddressInt::construct_from(slice).map(Address::Int).map_err(runtime_err)
+        } else {
+            MsgAddressExt::construct_from(slice).map(Address::Ext).map_err(runtime_err)
+        }
+    }
+    pub(crate) fn write(&self, builder: &mut BuilderData) -> PyResult<()> {
+        match self {
+            Address::Ext(address) => address.write_to(builder),
+            Address::Int(address) => address.write_to(builder),
+        }.map_err(runtime_err)
+    }
+}
+
+// Hex form of the slice bits with the completion tag, as accepted by SliceData::from_string
+fn bits_to_hex(slice: &SliceData) -> String {
+    let bits = slice.remaining_bits();
+    let mut bytes = slice.clone().get_next_bits(bits).unwrap_or_default();
+    let tagged = bits % 4 != 0;
+    if tagged {
+        bytes[bits / 8] |= 0x80 >> (bits % 8);
+    }
+    let hex = bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    let mut hex = hex[..bits.div_ceil(4)].to_string();
+    if tagged {
+        hex.push('_');
+    }
+    hex
+}
+
+fn hex_to_bits(hex: &str) -> PyResult<SliceData> {
+    SliceData::from_string(hex).or_else(|_| err!("invalid address bits \"{}\"", hex))
+}
+
+fn anycast_info(anycast: Option<PySlice>) -> PyResult<Option<AnycastInfo>> {
+    match anycast {
+        Some(prefix) => AnycastInfo::with_rewrite_pfx(prefix.slice).map(Some).map_err(runtime_err),
+        None => Ok(None),
+    }
+}
+
+fn internal(workchain: i32, address: SliceData, anycast: Option<AnycastInfo>) -> PyResult<Address> {
+    let address = if address.remaining_bits() == 256 && (-128..128).contains(&workchain) {
+        MsgAddressInt::with_standart(anycast, workchain as i8, address)
+    } else {
+        MsgAddressInt::with_variant(anycast, workchain, address)
+    };
+    address.map(Address::Int).map_err(runtime_err)
+}
+
+const FRIENDLY_BOUNCEABLE_TAG: u8 = 0x11;
+const FRIENDLY_NON_BOUNCEABLE_TAG: u8 = 0x51;
+const FRIENDLY_TESTNET_FLAG: u8 = 0x80;
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+// Parses the 36-byte user-friendly form encoded with either base64 alphabet,
+// returning the address along with its bounceable and testnet flags
+pub(crate) fn parse_friendly_address(text: &str) -> PyResult<(Address, bool, bool)> {
+    if text.len() != 48 {
+        return err!("friendly address \"{}\" must be 48 characters long", text)
+    }
+    let standard = text.replace('-', "+").replace('_', "/");
+    let bytes = ton_types::base64_decode(&standard)
+        .or_else(|_| err!("invalid base64 in friendly address \"{}\"", text))?;
+    if bytes.len() != 36 {
+        return err!("friendly address \"{}\" must contain 36 bytes", text)
+    }
+    if u16::from_be_bytes([bytes[34], bytes[35]]) != crc16(&bytes[..34]) {
+        return err!("friendly address \"{}\" has invalid checksum", text)
+    }
+    let bounceable = match bytes[0] & !FRIENDLY_TESTNET_FLAG {
+        FRIENDLY_BOUNCEABLE_TAG => true,
+        FRIENDLY_NON_BOUNCEABLE_TAG => false,
+        tag => return err!("friendly address \"{}\" has invalid tag {:02x}", text, tag),
+    };
+    let testnet = bytes[0] & FRIENDLY_TESTNET_FLAG != 0;
+    let address = SliceData::from_raw(bytes[2..34].to_vec(), 256);
+    let address = MsgAddressInt::with_standart(None, bytes[1] as i8, address).map_err(runtime_err)?;
+    Ok((Address::Int(address), bounceable, testnet))
+}
+
+// Parses the raw forms: "" for addr_none, ":bits" for addr_extern, "wc:bits" and "prefix:wc:bits" for internal addresses,
+// as well as the user-friendly form of std addresses
+pub(crate) fn parse_address(text: &str) -> PyResult<Address> {
+    let parts = text.split(':').collect::<Vec<_>>();
+    match parts.as_slice() {
+        [""] => Ok(Address::Ext(MsgAddressExt::AddrNone)),
+        [friendly] => parse_friendly_address(friendly).map(|(address, _, _)| address),
+        ["", bits] => MsgAddressExt::with_extern(hex_to_bits(bits)?)
+            .map(Address::Ext)
+            .map_err(runtime_err),
+        [workchain, bits] => {
+            let workchain = workchain.parse::<i32>()
+                .or_else(|_| err!("invalid workchain in address \"{}\"", text))?;
+            internal(workchain, hex_to_bits(bits)?, None)
+        }
+        [prefix, workchain, bits] => {
+            let workchain = workchain.parse::<i32>()
+                .or_else(|_| err!("invalid workchain in address \"{}\"", text))?;
+            let anycast = AnycastInfo::with_rewrite_pfx(hex_to_bits(prefix)?).map_err(runtime_err)?;
+            internal(workchain, hex_to_bits(bits)?, Some(anycast))
+        }
+        _ => err!("invalid address \"{}\"", text),
+    }
+}
+
+#[pyclass(name = "Address")]
+#[derive(Clone)]
+pub(crate) struct PyAddress {
+    pub(crate) address: Address,
+}
+
+impl PyAddress {
+    pub(crate) fn new(address: Address) -> Self {
+        Self { address }
+    }
+}
+
+#[pymethods]
+impl PyAddress {
+    #[new]
+    fn create(text: &str) -> PyResult<Self> {
+        parse_address(text).map(Self::new)
+    }
+    #[staticmethod]
+    fn from_friendly(text: &str) -> PyResult<(Self, bool, bool)> {
+        let (address, bounceable, testnet) = parse_friendly_address(text)?;
+        Ok((Self::new(address), bounceable, testnet))
+    }
+    #[pyo3(signature = (bounceable = true, testnet = false, url_safe = true))]
+    fn to_friendly(&self, bounceable: bool, testnet: bool, url_safe: bool) -> PyResult<String> {
+        let address = match &self.address {
+            Address::Int(MsgAddressInt::AddrStd(address)) if address.anycast.is_none() => address,
+            _ => return err!("only std addresses without anycast have the friendly form"),
+        };
+        let mut tag = if bounceable { FRIENDLY_BOUNCEABLE_TAG } else { FRIENDLY_NON_BOUNCEABLE_TAG };
+        if testnet {
+            tag |= FRIENDLY_TESTNET_FLAG;
+        }
+        let mut bytes = vec!(tag, address.workchain_id as u8);
+        bytes.extend_from_slice(&address.address.clone().get_next_bits(256).map_err(runtime_err)?);
+        let crc = crc16(&bytes);
+        bytes.extend_from_slice(&crc.to_be_bytes());
+        let text = ton_types::base64_encode(bytes);
+        Ok(if url_safe { text.replace('+', "-").replace('/', "_") } else { text })
+    }
+    #[staticmethod]
+    fn none() -> Self {
+        Self::new(Address::Ext(MsgAddressExt::AddrNone))
+    }
+    #[staticmethod]
+    fn external(bits: PySlice) -> PyResult<Self> {
+        MsgAddressExt::with_extern(bits.slice)
+            .map(|address| Self::new(Address::Ext(address)))
+            .map_err(runtime_err)
+    }
+    #[staticmethod]
+    #[pyo3(signature = (workchain, address, anycast = None))]
+    fn std(workchain: i8, address: BigUint, anycast: Option<PySlice>) -> PyResult<Self> {
+        if address.bits() > 256 {
+            return err!("std address must fit into 256 bits, got {} bits", address.bits())
+        }
+        let bytes = unsigned_int_serialize(BigInt::from(address), 256)?;
+        let address = SliceData::from_raw(bytes, 256);
+        MsgAddressInt::with_standart(anycast_info(anycast)?, workchain, address)
+            .map(|address| Self::new(Address::Int(address)))
+            .map_err(runtime_err)
+    }
+    #[staticmethod]
+    #[pyo3(signature = (workchain, bits, anycast = None))]
+    fn var(workchain: i32, bits: PySlice, anycast: Option<PySlice>) -> PyResult<Self> {
+        MsgAddressInt::with_variant(anycast_info(anycast)?, workchain, bits.slice)
+            .map(|address| Self::new(Address::Int(address)))
+            .map_err(runtime_err)
+    }
+    #[getter]
+    fn kind(&self) -> &'static str {
+        match &self.address {
+            Address::Ext(MsgAddressExt::AddrNone) => "none",
+            Address::Ext(MsgAddressExt::AddrExtern(_)) => "extern",
+            Address::Int(MsgAddressInt::AddrStd(_)) => "std",
+            Address::Int(MsgAddressInt::AddrVar(_)) => "var",
+        }
+    }
+    fn is_internal(&self) -> bool {
+        matches!(self.address, Address::Int(_))
+    }
+    #[getter]
+    fn workchain(&self) -> Option<i32> {
+        match &self.address {
+            Address::Int(address) => Some(address.workchain_id()),
+            Address::Ext(_) => None,
+        }
+    }
+    #[getter]
+    fn bits(&self) -> PySlice {
+        let bits = match &self.address {
+            Address::Ext(MsgAddressExt::AddrNone) => SliceData::default(),
+            Address::Ext(MsgAddressExt::AddrExtern(address)) => address.external_address.clone(),
+            Address::Int(address) => address.address(),
+        };
+        PySlice::new(bits)
+    }
+    #[getter]
+    fn address(&self) -> PyResult<BigUint> {
+        let bits = self.bits().slice;
+        let len = bits.remaining_bits();
+        let bytes = bits.clone().get_next_bits(len).map_err(runtime_err)?;
+        unsigned_int_deserialize(&bytes, len)
+    }
+    #[getter]
+    fn anycast(&self) -> Option<PySlice> {
+        let anycast = match &self.address {
+            Address::Int(MsgAddressInt::AddrStd(address)) => address.anycast.as_ref(),
+            Address::Int(MsgAddressInt::AddrVar(address)) => address.anycast.as_ref(),
+            Address::Ext(_) => None,
+        };
+        anycast.map(|anycast| PySlice::new(anycast.rewrite_pfx.clone()))
+    }
+    fn __str__(&self) -> String {
+        match &self.address {
+            Address::Ext(MsgAddressExt::AddrNone) => String::new(),
+            Address::Ext(MsgAddressExt::AddrExtern(address)) => format!(":{}", bits_to_hex(&address.external_address)),
+            Address::Int(address) => {
+                let prefix = self.anycast()
+                    .map(|anycast| format!("{}:", bits_to_hex(&anycast.slice)))
+                    .unwrap_or_default();
+                format!("{}{}:{}", prefix, address.workchain_id(), bits_to_hex(&address.address()))
+            }
+        }
+    }
+    fn __repr__(&self) -> String {
+        format!("Address(\"{}\")", self.__str__())
+    }
+    fn __richcmp__(&self, other: Self, op: CompareOp, py: Python<'_>) -> PyObject {
+        match op {
+            CompareOp::Eq => (self.address == other.address).into_py(py),
+            CompareOp::Ne => (self.address != other.address).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+}
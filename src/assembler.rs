@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use crate::{PyCell, utils::{hash_to_int, AssemblerError}};
+use num_bigint::BigUint;
+use pyo3::prelude::*;
+use ton_labs_assembler::{CompileError, Line};
+
+// further errors are looked for by blanking out the failed line and compiling again
+const MAX_ERRORS: usize = 32;
+
+struct ErrorInfo {
+    line: usize,
+    column: usize,
+    token: Option<String>,
+    message: String,
+}
+
+impl ErrorInfo {
+    fn new(err: &CompileError) -> Self {
+        let (pos, token) = match err {
+            CompileError::Syntax(pos, _) => (pos, None),
+            CompileError::UnknownOperation(pos, name) => (pos, Some(name.clone())),
+            CompileError::Operation(pos, name, _) => (pos, Some(name.clone())),
+        };
+        Self { line: pos.line, column: pos.column, token, message: err.to_string() }
+    }
+}
+
+fn assembler_error(py: Python<'_>, errors: Vec<ErrorInfo>) -> PyResult<PyErr> {
+    let first = &errors[0];
+    let err = AssemblerError::new_err(first.message.clone());
+    let value = err.value(py);
+    value.setattr("line", first.line)?;
+    value.setattr("column", first.column)?;
+    value.setattr("token", first.token.clone())?;
+    value.setattr("message", first.message.clone())?;
+    let errors = errors.into_iter()
+        .map(|info| (info.line, info.column, info.token, info.message))
+        .collect::<Vec<_>>();
+    value.setattr("errors", errors)?;
+    Ok(err)
+}
+
+fn compile<T>(py: Python<'_>, code: &str, compile: impl Fn(&str) -> Result<T, CompileError>) -> PyResult<T> {
+    let mut errors = match compile(code) {
+        Ok(result) => return Ok(result),
+        Err(err) => vec!(ErrorInfo::new(&err)),
+    };
+    // syntax errors and lines with blocks can't be skipped without breaking the rest of the code
+    let mut lines = code.lines().map(String::from).collect::<Vec<_>>();
+    while errors.len() < MAX_ERRORS {
+        let last = errors.last().unwrap();
+        if last.token.is_none() {
+            break
+        }
+        let Some(text) = last.line.checked_sub(1).and_then(|index| lines.get_mut(index))
+            else { break };
+        if text.contains('{') || text.contains('}') {
+            break
+        }
+        text.clear();
+        match compile(&lines.join("\n")) {
+            Ok(_) => break,
+            Err(err) => {
+                let info = ErrorInfo::new(&err);
+                if info.line <= last.line {
+                    break
+                }
+                errors.push(info)
+            }
+        }
+    }
+    Err(assembler_error(py, errors)?)
+}
+
+#[pyfunction]
+pub(crate) fn assemble(py: Python<'_>, code: String) -> PyResult<PyCell> {
+    let slice = compile(py, &code, ton_labs_assembler::compile_code)?;
+    Ok(PyCell::new(slice.cell().clone()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (code, filename = "<code>"))]
+pub(crate) fn assemble_debug(py: Python<'_>, code: String, filename: &str) -> PyResult<(PyCell, HashMap<(BigUint, usize), (String, usize)>)> {
+    let (slice, dbg_info) = compile(py, &code, |code| {
+        let lines = code.lines().enumerate()
+            .map(|(index, text)| Line::new(&format!("{}\n", text), filename, index + 1))
+            .collect::<Vec<_>>();
+        ton_labs_assembler::compile_code_debuggable(lines)
+    })?;
+    let mut debug_map = HashMap::new();
+    for (cell_hash, offsets) in dbg_info.iter() {
+        for (offset, pos) in offsets.iter() {
+            debug_map.insert((hash_to_int(cell_hash), *offset), (pos.filename.clone(), pos.line));
+        }
+    }
+    Ok((PyCell::new(slice.cell().clone()), debug_map))
+}
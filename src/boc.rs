@@ -0,0 +1,591 @@
+use std::collections::HashMap;
+use crate::{err, PyCell, utils::{boc_err, BocError}};
+use pyo3::{
+    prelude::*,
+    types::PyBytes,
+};
+use ton_types::{BocWriter, Cell, CellType, UInt256};
+
+const BOC_GENERIC_TAG: u32 = 0xb5ee9c72;
+const BOC_INDEXED_TAG: u32 = 0x68ff65f3;
+const BOC_INDEXED_CRC32_TAG: u32 = 0xacc3a728;
+
+macro_rules! boc_fail {
+    ($($arg:tt)+) => {
+        PyResult::Err(BocError::new_err(format!($($arg)+)))
+    };
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read(&mut self, size: usize) -> PyResult<usize> {
+        if size > 8 || self.pos + size > self.data.len() {
+            return boc_fail!("unexpected end of boc")
+        }
+        let value = self.data[self.pos..self.pos + size].iter()
+            .fold(0, |value, byte| (value << 8) | *byte as usize);
+        self.pos += size;
+        Ok(value)
+    }
+}
+
+// Offset and size of the data of a serialized cell, past its descriptors and stored hashes
+pub(crate) fn cell_data_range(raw: &[u8]) -> (usize, usize) {
+    let (d1, d2) = (raw[0] as usize, raw[1] as usize);
+    let hashes_size = if d1 & 16 != 0 {
+        ((d1 >> 5).count_ones() as usize + 1) * (32 + 2)
+    } else {
+        0
+    };
+    (2 + hashes_size, (d2 >> 1) + (d2 & 1))
+}
+
+fn cell_size(data: &[u8], ref_size: usize) -> PyResult<usize> {
+    if data.len() < 2 {
+        return boc_fail!("unexpected end of boc")
+    }
+    let (start, size) = cell_data_range(data);
+    Ok(start + size + (data[0] as usize & 7) * ref_size)
+}
+
+pub(crate) fn read_ref(data: &[u8], ref_size: usize) -> usize {
+    data[..ref_size].iter().fold(0, |value, byte| (value << 8) | *byte as usize)
+}
+
+// Header and cell offsets of a serialized bag of cells
+pub(crate) struct BocLayout {
+    pub(crate) magic: u32,
+    pub(crate) flags: u8,
+    pub(crate) has_index: bool,
+    pub(crate) has_crc: bool,
+    pub(crate) has_cache_bits: bool,
+    pub(crate) ref_size: usize,
+    pub(crate) offset_size: usize,
+    pub(crate) absent: usize,
+    pub(crate) roots: Vec<usize>,
+    pub(crate) cells_start: usize,
+    // start offsets of the cells relative to cells_start, followed by the end offset of the last cell
+    pub(crate) offsets: Vec<usize>,
+}
+
+impl BocLayout {
+    pub(crate) fn parse(data: &[u8]) -> PyResult<Self> {
+        let mut reader = Reader { data, pos: 0 };
+        let magic = reader.read(4)? as u32;
+        let (flags, has_index, has_crc, has_cache_bits, ref_size) = match magic {
+            BOC_GENERIC_TAG => {
+                let flags = reader.read(1)?;
+                ((flags >> 3) as u8 & 3, flags & 0x80 != 0, flags & 0x40 != 0, flags & 0x20 != 0, flags & 7)
+            }
+            BOC_INDEXED_TAG => (0, true, false, false, reader.read(1)?),
+            BOC_INDEXED_CRC32_TAG => (0, true, true, false, reader.read(1)?),
+            _ => return boc_fail!("unknown boc magic {:08x}", magic),
+        };
+        if ref_size == 0 {
+            return boc_fail!("invalid boc reference size 0")
+        }
+        let offset_size = reader.read(1)?;
+        let cells = reader.read(ref_size)?;
+        let roots_count = reader.read(ref_size)?;
+        let absent = reader.read(ref_size)?;
+        let total_size = reader.read(offset_size)?;
+        let mut roots = Vec::new();
+        for _ in 0..roots_count {
+            roots.push(if magic == BOC_GENERIC_TAG { reader.read(ref_size)? } else { 0 });
+        }
+        let mut index = Vec::new();
+        if has_index {
+            for _ in 0..cells {
+                let end = reader.read(offset_size)?;
+                index.push(if has_cache_bits { end >> 1 } else { end });
+            }
+        }
+        let cells_start = reader.pos;
+        let end = cells_start.checked_add(total_size).and_then(|end| end.checked_add(if has_crc { 4 } else { 0 }));
+        if end.is_none_or(|end| end > data.len()) {
+            return boc_fail!("unexpected end of boc")
+        }
+        // cells are scanned once to check their layout, so that they can be loaded later without errors
+        let cells_data = &data[cells_start..cells_start + total_size];
+        let mut offsets = vec!(0);
+        let mut pos = 0;
+        for i in 0..cells {
+            let size = cell_size(&cells_data[pos..], ref_size)?;
+            if pos + size > total_size {
+                return boc_fail!("unexpected end of boc")
+            }
+            let refs = cells_data[pos] as usize & 7;
+            let refs_start = pos + size - refs * ref_size;
+            for r in 0..refs {
+                let child = read_ref(&cells_data[refs_start + r * ref_size..], ref_size);
+                if child <= i || child >= cells {
+                    return boc_fail!("cell {} has invalid reference {}", i, child)
+                }
+            }
+            pos += size;
+            if has_index && index[i] != pos {
+                return boc_fail!("boc index mismatch at cell {}", i)
+            }
+            offsets.push(pos);
+        }
+        if pos != total_size {
+            return boc_fail!("boc cells size mismatch")
+        }
+        if let Some(root) = roots.iter().find(|root| **root >= cells) {
+            return boc_fail!("root index {} is out of range", root)
+        }
+        Ok(Self { magic, flags, has_index, has_crc, has_cache_bits, ref_size, offset_size, absent, roots, cells_start, offsets })
+    }
+    pub(crate) fn cells(&self) -> usize {
+        self.offsets.len() - 1
+    }
+    pub(crate) fn total_size(&self) -> usize {
+        self.offsets[self.cells()]
+    }
+    pub(crate) fn raw<'a>(&self, data: &'a [u8], index: usize) -> &'a [u8] {
+        &data[self.cells_start + self.offsets[index]..self.cells_start + self.offsets[index + 1]]
+    }
+    pub(crate) fn references(&self, data: &[u8], index: usize) -> Vec<usize> {
+        let raw = self.raw(data, index);
+        let refs = raw[0] as usize & 7;
+        let start = raw.len() - refs * self.ref_size;
+        (0..refs).map(|i| read_ref(&raw[start + i * self.ref_size..], self.ref_size)).collect()
+    }
+    // Reports the deviations from the canonical serialization produced by the reference implementation
+    fn validate(&self, data: &[u8]) -> Vec<String> {
+        let mut issues = Vec::new();
+        let cells = self.cells();
+        if self.ref_size > bytes_to_fit(cells) {
+            issues.push(format!("size_bytes {} is larger than needed {}", self.ref_size, bytes_to_fit(cells)));
+        }
+        // index entries are doubled to make room for the cache bits
+        let needed = bytes_to_fit(self.total_size() << self.has_cache_bits as usize);
+        if self.offset_size > needed {
+            issues.push(format!("offset_bytes {} is larger than needed {}", self.offset_size, needed));
+        }
+        let end = self.cells_start + self.total_size();
+        if self.has_crc {
+            let expected = u32::from_le_bytes([data[end], data[end + 1], data[end + 2], data[end + 3]]);
+            let actual = crc32c(&data[..end]);
+            if expected != actual {
+                issues.push(format!("crc mismatch: stored {:08x}, computed {:08x}", expected, actual));
+            }
+        }
+        let end = end + if self.has_crc { 4 } else { 0 };
+        if data.len() > end {
+            issues.push(format!("{} trailing bytes after boc", data.len() - end));
+        }
+        for i in 0..cells {
+            let raw = self.raw(data, i);
+            if raw[0] & 7 > 4 {
+                issues.push(format!("cell {} has {} references", i, raw[0] & 7));
+            }
+            let (start, size) = cell_data_range(raw);
+            if raw[1] & 1 == 1 {
+                match raw[start + size - 1] {
+                    0 => issues.push(format!("cell {} has no completion tag", i)),
+                    0x80 => issues.push(format!("cell {} has byte-aligned data with a completion tag", i)),
+                    _ => (),
+                }
+            }
+        }
+        // identical cells share the same id, children always follow their parents
+        let mut ids = vec!(0; cells);
+        let mut seen = HashMap::new();
+        for i in (0..cells).rev() {
+            let raw = self.raw(data, i);
+            let (start, size) = cell_data_range(raw);
+            let mut key = vec!(raw[0] & 0xef, raw[1]);
+            key.extend_from_slice(&raw[start..start + size]);
+            for child in self.references(data, i) {
+                key.extend_from_slice(&ids[child].to_be_bytes());
+            }
+            let next = seen.len();
+            ids[i] = *seen.entry(key).or_insert(next);
+        }
+        let mut first = HashMap::new();
+        for (i, id) in ids.into_iter().enumerate() {
+            let original = *first.entry(id).or_insert(i);
+            if original != i {
+                issues.push(format!("cell {} duplicates cell {}", i, original));
+            }
+        }
+        issues
+    }
+}
+
+// Cell of a bag being serialized the way the reference implementation (crypto/vm/boc.cpp) does it
+struct FiftCell {
+    cell: Cell,
+    refs: Vec<usize>,
+    // weight of the subtree, zero for the cells whose subtrees are visited before the others
+    weight: usize,
+    new_index: isize,
+}
+
+const MAX_CELL_WEIGHT: usize = 64;
+
+struct FiftBoc {
+    cells: Vec<FiftCell>,
+    indexes: HashMap<UInt256, usize>,
+    order: Vec<usize>,
+}
+
+impl FiftBoc {
+    fn import(&mut self, cell: &Cell) -> usize {
+        if let Some(index) = self.indexes.get(&cell.repr_hash()) {
+            return *index
+        }
+        let mut refs = Vec::new();
+        let mut weight = 1;
+        for i in 0..cell.references_count() {
+            let child = self.import(&cell.reference(i).expect("reference index is in range"));
+            weight += self.cells[child].weight;
+            refs.push(child);
+        }
+        self.indexes.insert(cell.repr_hash(), self.cells.len());
+        self.cells.push(FiftCell { cell: cell.clone(), refs, weight: weight.min(255), new_index: -1 });
+        self.cells.len() - 1
+    }
+    fn reorder(&mut self) {
+        for i in (0..self.cells.len()).rev() {
+            let refs = self.cells[i].refs.clone();
+            let count = refs.len();
+            let mut remaining = count;
+            let mut sum = MAX_CELL_WEIGHT - 1;
+            let mut light = vec!(false; count);
+            for (j, child) in refs.iter().enumerate() {
+                if self.cells[*child].weight <= (MAX_CELL_WEIGHT - 1 + j) / count {
+                    sum -= self.cells[*child].weight;
+                    remaining -= 1;
+                    light[j] = true;
+                }
+            }
+            if remaining > 0 {
+                for (j, child) in refs.iter().enumerate() {
+                    if !light[j] {
+                        let limit = sum / remaining;
+                        sum += 1;
+                        self.cells[*child].weight = self.cells[*child].weight.min(limit);
+                    }
+                }
+            }
+        }
+        for i in 0..self.cells.len() {
+            let sum = 1 + self.cells[i].refs.iter().map(|child| self.cells[*child].weight).sum::<usize>();
+            self.cells[i].weight = if sum <= self.cells[i].weight { sum } else { 0 };
+        }
+    }
+    // mode 0 previsits the cell, mode 1 visits its children, mode 2 assigns the index to the cell
+    fn revisit(&mut self, index: usize, mode: u8) -> isize {
+        let new_index = self.cells[index].new_index;
+        if new_index >= 0 {
+            return new_index
+        }
+        let refs = self.cells[index].refs.clone();
+        if mode == 0 {
+            if new_index != -1 {
+                return new_index
+            }
+            for child in refs.iter().rev() {
+                let special = self.cells[*child].weight == 0;
+                self.revisit(*child, special as u8);
+            }
+            self.cells[index].new_index = -2;
+            return -2
+        }
+        if mode == 2 {
+            self.cells[index].new_index = self.order.len() as isize;
+            self.order.push(index);
+            return self.cells[index].new_index
+        }
+        if new_index == -3 {
+            return new_index
+        }
+        if self.cells[index].weight == 0 {
+            self.revisit(index, 0);
+        }
+        for child in refs.iter().rev() {
+            self.revisit(*child, 1);
+        }
+        for child in refs.iter().rev() {
+            self.revisit(*child, 2);
+        }
+        self.cells[index].new_index = -3;
+        -3
+    }
+}
+
+fn serialize_cell(cell: &Cell, refs: impl Iterator<Item = usize>, ref_size: usize, bytes: &mut Vec<u8>) {
+    let bits = cell.bit_length();
+    let exotic = cell.cell_type() != CellType::Ordinary;
+    bytes.push(cell.references_count() as u8 | (exotic as u8) << 3 | cell.level_mask().mask() << 5);
+    bytes.push((bits / 8 + bits.div_ceil(8)) as u8);
+    let mut data = cell.data()[..bits.div_ceil(8)].to_vec();
+    if bits % 8 != 0 {
+        let last = data.len() - 1;
+        let tag = 0x80 >> (bits % 8);
+        data[last] = (data[last] & !(tag * 2 - 1)) | tag;
+    }
+    bytes.extend_from_slice(&data);
+    for child in refs {
+        bytes.extend_from_slice(&child.to_be_bytes()[std::mem::size_of::<usize>() - ref_size..]);
+    }
+}
+
+// Serializes the roots byte-for-byte as Fift does, reproducing its cell ordering
+pub(crate) fn write_fift(roots: &[Cell], index: bool, crc: bool) -> PyResult<Vec<u8>> {
+    let mut boc = FiftBoc { cells: Vec::new(), indexes: HashMap::new(), order: Vec::new() };
+    let root_indexes = roots.iter().map(|root| boc.import(root)).collect::<Vec<_>>();
+    boc.reorder();
+    for root in &root_indexes {
+        boc.revisit(*root, 0);
+        boc.revisit(*root, 1);
+    }
+    for root in &root_indexes {
+        boc.revisit(*root, 2);
+    }
+    let count = boc.cells.len();
+    // the reference implementation allocates children first and writes the cells in reverse
+    let position = |cell: &FiftCell| count - 1 - cell.new_index as usize;
+    let ref_size = bytes_to_fit(count);
+    let mut cells_data = Vec::new();
+    let mut ends = Vec::new();
+    for i in boc.order.iter().rev() {
+        let cell = &boc.cells[*i];
+        let refs = cell.refs.iter().map(|child| position(&boc.cells[*child]));
+        serialize_cell(&cell.cell, refs, ref_size, &mut cells_data);
+        ends.push(cells_data.len());
+    }
+    let offset_size = bytes_to_fit(cells_data.len());
+    if ref_size > 4 || offset_size > 8 {
+        return boc_fail!("boc is too large")
+    }
+    let write = |bytes: &mut Vec<u8>, value: usize, size: usize| {
+        bytes.extend_from_slice(&value.to_be_bytes()[std::mem::size_of::<usize>() - size..]);
+    };
+    let mut bytes = BOC_GENERIC_TAG.to_be_bytes().to_vec();
+    bytes.push((index as u8) << 7 | (crc as u8) << 6 | ref_size as u8);
+    bytes.push(offset_size as u8);
+    write(&mut bytes, count, ref_size);
+    write(&mut bytes, roots.len(), ref_size);
+    write(&mut bytes, 0, ref_size);
+    write(&mut bytes, cells_data.len(), offset_size);
+    for root in &root_indexes {
+        write(&mut bytes, position(&boc.cells[*root]), ref_size);
+    }
+    if index {
+        for end in ends {
+            write(&mut bytes, end, offset_size);
+        }
+    }
+    bytes.extend_from_slice(&cells_data);
+    if crc {
+        let crc = crc32c(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+    }
+    Ok(bytes)
+}
+
+fn bytes_to_fit(value: usize) -> usize {
+    std::cmp::max(1, (usize::BITS - value.leading_zeros()) as usize).div_ceil(8)
+}
+
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0x82f63b78 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn hex_decode(text: &str) -> PyResult<Vec<u8>> {
+    if text.len() % 2 != 0 || !text.is_ascii() {
+        return err!("invalid hex string")
+    }
+    (0..text.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .or_else(|_| err!("invalid hex string"))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn read_roots(bytes: &[u8]) -> PyResult<Vec<PyCell>> {
+    let result = ton_types::read_boc(bytes).map_err(boc_err)?;
+    Ok(result.roots.into_iter().map(PyCell::new).collect())
+}
+
+// Rewrites an indexed boc so that its index entries are doubled and carry the cache bits
+// of the cells referenced more than once, as BocWriter doesn't produce them
+fn add_cache_bits(bytes: &[u8], offset_size: Option<usize>) -> PyResult<Vec<u8>> {
+    let layout = BocLayout::parse(bytes)?;
+    let cells = layout.cells();
+    let mut parents = vec!(0; cells);
+    for i in 0..cells {
+        for child in layout.references(bytes, i) {
+            parents[child] += 1;
+        }
+    }
+    let needed = bytes_to_fit(layout.total_size() * 2);
+    let offset_size = offset_size.unwrap_or(needed);
+    if offset_size < needed {
+        return boc_fail!("offset_size {} is too small for cache bits, {} is needed", offset_size, needed)
+    }
+    let write = |bytes: &mut Vec<u8>, value: usize, size: usize| {
+        bytes.extend_from_slice(&value.to_be_bytes()[std::mem::size_of::<usize>() - size..]);
+    };
+    let ref_size = layout.ref_size;
+    // magic, flags and sizes, followed by the counts of cells, roots and absent cells
+    let mut output = bytes[..6 + 3 * ref_size].to_vec();
+    output[4] |= 0x20;
+    output[5] = offset_size as u8;
+    write(&mut output, layout.total_size(), offset_size);
+    let roots_start = 6 + 3 * ref_size + layout.offset_size;
+    output.extend_from_slice(&bytes[roots_start..roots_start + layout.roots.len() * ref_size]);
+    for i in 0..cells {
+        write(&mut output, layout.offsets[i + 1] * 2 + (parents[i] > 1) as usize, offset_size);
+    }
+    output.extend_from_slice(&bytes[layout.cells_start..layout.cells_start + layout.total_size()]);
+    if layout.has_crc {
+        let crc = crc32c(&output);
+        output.extend_from_slice(&crc.to_le_bytes());
+    }
+    Ok(output)
+}
+
+fn write_roots(
+    roots: Vec<PyCell>,
+    index: bool,
+    crc: bool,
+    ref_size: Option<usize>,
+    offset_size: Option<usize>,
+    cache_bits: bool,
+) -> PyResult<Vec<u8>> {
+    let roots = roots.into_iter().map(|root| root.cell).collect::<Vec<Cell>>();
+    let writer = BocWriter::with_roots(roots).map_err(boc_err)?;
+    let mut bytes = Vec::new();
+    if cache_bits {
+        // cache bits are stored in the index, so it is always written along with them
+        writer.write_ex(&mut bytes, true, crc, ref_size, None).map_err(boc_err)?;
+        return add_cache_bits(&bytes, offset_size)
+    }
+    writer.write_ex(&mut bytes, index, crc, ref_size, offset_size).map_err(boc_err)?;
+    Ok(bytes)
+}
+
+#[pyclass(get_all, name = "BocInfo")]
+pub(crate) struct PyBocInfo {
+    magic: u32,
+    flags: u8,
+    has_index: bool,
+    has_crc: bool,
+    has_cache_bits: bool,
+    size_bytes: usize,
+    offset_bytes: usize,
+    cells: usize,
+    roots: Vec<usize>,
+    absent: usize,
+    total_cells_size: usize,
+    issues: Option<Vec<String>>,
+}
+
+#[pymethods]
+impl PyBocInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "BocInfo(magic={:08x}, flags={}, has_index={}, has_crc={}, has_cache_bits={}, size_bytes={}, \
+            offset_bytes={}, cells={}, roots={:?}, absent={}, total_cells_size={}, issues={:?})",
+            self.magic, self.flags, self.has_index, self.has_crc, self.has_cache_bits, self.size_bytes,
+            self.offset_bytes, self.cells, self.roots, self.absent, self.total_cells_size, self.issues,
+        )
+    }
+}
+
+#[pyclass(name = "Boc")]
+pub(crate) struct PyBoc { }
+
+#[pymethods]
+impl PyBoc {
+    #[staticmethod]
+    fn read(bytes: Vec<u8>) -> PyResult<Vec<PyCell>> {
+        read_roots(&bytes)
+    }
+    #[staticmethod]
+    #[pyo3(signature = (roots, index = false, crc = false, ref_size = None, offset_size = None, cache_bits = false))]
+    fn write(
+        py: Python<'_>,
+        roots: Vec<PyCell>,
+        index: bool,
+        crc: bool,
+        ref_size: Option<usize>,
+        offset_size: Option<usize>,
+        cache_bits: bool,
+    ) -> PyResult<PyObject> {
+        let bytes = write_roots(roots, index, crc, ref_size, offset_size, cache_bits)?;
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+    #[staticmethod]
+    #[pyo3(signature = (bytes, strict = false))]
+    fn inspect(bytes: Vec<u8>, strict: bool) -> PyResult<PyBocInfo> {
+        let layout = BocLayout::parse(&bytes)?;
+        Ok(PyBocInfo {
+            magic: layout.magic,
+            flags: layout.flags,
+            has_index: layout.has_index,
+            has_crc: layout.has_crc,
+            has_cache_bits: layout.has_cache_bits,
+            size_bytes: layout.ref_size,
+            offset_bytes: layout.offset_size,
+            cells: layout.cells(),
+            roots: layout.roots.clone(),
+            absent: layout.absent,
+            total_cells_size: layout.total_size(),
+            issues: if strict { Some(layout.validate(&bytes)) } else { None },
+        })
+    }
+    #[staticmethod]
+    fn read_base64(text: &str) -> PyResult<Vec<PyCell>> {
+        let bytes = ton_types::base64_decode(text).map_err(boc_err)?;
+        read_roots(&bytes)
+    }
+    #[staticmethod]
+    #[pyo3(signature = (roots, index = false, crc = false, ref_size = None, offset_size = None, cache_bits = false))]
+    fn write_base64(
+        roots: Vec<PyCell>,
+        index: bool,
+        crc: bool,
+        ref_size: Option<usize>,
+        offset_size: Option<usize>,
+        cache_bits: bool,
+    ) -> PyResult<String> {
+        let bytes = write_roots(roots, index, crc, ref_size, offset_size, cache_bits)?;
+        Ok(ton_types::base64_encode(bytes))
+    }
+    #[staticmethod]
+    fn read_hex(text: &str) -> PyResult<Vec<PyCell>> {
+        read_roots(&hex_decode(text.trim())?)
+    }
+    #[staticmethod]
+    #[pyo3(signature = (roots, index = false, crc = false, ref_size = None, offset_size = None, cache_bits = false))]
+    fn write_hex(
+        roots: Vec<PyCell>,
+        index: bool,
+        crc: bool,
+        ref_size: Option<usize>,
+        offset_size: Option<usize>,
+        cache_bits: bool,
+    ) -> PyResult<String> {
+        let bytes = write_roots(roots, index, crc, ref_size, offset_size, cache_bits)?;
+        Ok(hex_encode(&bytes))
+    }
+}
@@ -3,7 +3,6 @@ use pyo3::{
     prelude::*,
     basic::CompareOp,
     types::PyList,
-    exceptions::PyRuntimeError,
 };
 use ton_vm::stack::{
     continuation::{ContinuationData, ContinuationType},
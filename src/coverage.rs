@@ -0,0 +1,65 @@
+use std::collections::{BTreeMap, HashMap};
+use crate::{trace::TraceRecord, utils::{hash_from_int, hash_to_int}};
+use num_bigint::BigUint;
+use pyo3::prelude::*;
+use ton_types::UInt256;
+
+#[derive(Clone, Default)]
+#[pyclass(name = "Coverage")]
+pub(crate) struct PyCoverage {
+    // hit counts keyed by code cell representation hash and bit offset of the instruction
+    hits: HashMap<(UInt256, usize), u64>,
+}
+
+impl PyCoverage {
+    pub(crate) fn add(&mut self, record: &TraceRecord) {
+        *self.hits.entry((record.cell_hash.clone(), record.offset)).or_default() += 1;
+    }
+    pub(crate) fn extend(&mut self, other: PyCoverage) {
+        for (position, count) in other.hits {
+            *self.hits.entry(position).or_default() += count;
+        }
+    }
+}
+
+#[pymethods]
+impl PyCoverage {
+    #[new]
+    fn create() -> Self {
+        Self::default()
+    }
+    fn merge(mut slf: PyRefMut<Self>, other: PyCoverage) -> PyRefMut<Self> {
+        slf.extend(other);
+        slf
+    }
+    fn hits(&self) -> HashMap<(BigUint, usize), u64> {
+        self.hits.iter()
+            .map(|((hash, offset), count)| ((hash_to_int(hash), *offset), *count))
+            .collect()
+    }
+    fn count(&self, cell_hash: BigUint, offset: usize) -> PyResult<u64> {
+        let hash = hash_from_int(&cell_hash)?;
+        Ok(self.hits.get(&(hash, offset)).copied().unwrap_or_default())
+    }
+    fn lcov(&self, debug_map: HashMap<(BigUint, usize), (String, usize)>) -> PyResult<String> {
+        let mut files = BTreeMap::<String, BTreeMap<usize, u64>>::new();
+        for ((cell_hash, offset), (filename, line)) in debug_map {
+            let hash = hash_from_int(&cell_hash)?;
+            let count = self.hits.get(&(hash, offset)).copied().unwrap_or_default();
+            *files.entry(filename).or_default().entry(line).or_default() += count;
+        }
+        let mut output = String::new();
+        for (filename, lines) in files {
+            output += &format!("TN:\nSF:{}\n", filename);
+            for (line, count) in lines.iter() {
+                output += &format!("DA:{},{}\n", line, count);
+            }
+            let hit = lines.values().filter(|count| **count > 0).count();
+            output += &format!("LF:{}\nLH:{}\nend_of_record\n", lines.len(), hit);
+        }
+        Ok(output)
+    }
+    fn __len__(&self) -> usize {
+        self.hits.len()
+    }
+}
@@ -1,7 +1,7 @@
+use crate::utils::PlaygroundError;
 use ed25519_dalek::Signer;
 use pyo3::{
     prelude::*,
-    exceptions::PyRuntimeError,
     types::{PyBytes, PyTuple},
 };
 
@@ -17,7 +17,7 @@ pub(crate) fn ed25519_new_keypair<'a>(py: Python<'a>) -> PyResult<&PyTuple> {
 
 pub(crate) fn load_secret(secret: &PyBytes) -> PyResult<ed25519_dalek::SecretKey> {
     let secret = ed25519_dalek::SecretKey::from_bytes(secret.as_bytes())
-        .map_err(|err| PyRuntimeError::new_err(format!("invalid secret bytes: {}", err)))?;
+        .map_err(|err| PlaygroundError::new_err(format!("invalid secret bytes: {}", err)))?;
     Ok(secret)
 }
 
@@ -0,0 +1,68 @@
+use crate::{PyCell, exotic::cell_type_name};
+use pyo3::prelude::*;
+use ton_types::Cell;
+
+fn bit(data: &[u8], index: usize) -> bool {
+    data[index / 8] & (0x80 >> (index % 8)) != 0
+}
+
+// Offset of the first differing bit, if the bitstrings are different
+fn first_different_bit(a: &Cell, b: &Cell) -> Option<usize> {
+    let len = a.bit_length().min(b.bit_length());
+    match (0..len).find(|i| bit(a.data(), *i) != bit(b.data(), *i)) {
+        Some(offset) => Some(offset),
+        None if a.bit_length() != b.bit_length() => Some(len),
+        None => None,
+    }
+}
+
+#[pyclass(get_all, name = "CellDiff")]
+pub(crate) struct PyCellDiff {
+    path: Vec<usize>,
+    bit_offset: Option<usize>,
+    a: PyCell,
+    b: PyCell,
+}
+
+#[pymethods]
+impl PyCellDiff {
+    fn __str__(&self) -> String {
+        let (a, b) = (&self.a.cell, &self.b.cell);
+        let mut reasons = Vec::new();
+        if let Some(offset) = self.bit_offset {
+            reasons.push(format!("bits differ at {}: x{{{}}} != x{{{}}}", offset, a.to_hex_string(true), b.to_hex_string(true)));
+        }
+        if a.references_count() != b.references_count() {
+            reasons.push(format!("refs {} != {}", a.references_count(), b.references_count()));
+        }
+        if a.cell_type() != b.cell_type() {
+            reasons.push(format!("type {} != {}", cell_type_name(a.cell_type()), cell_type_name(b.cell_type())));
+        }
+        format!("{:?}: {}", self.path, reasons.join(", "))
+    }
+}
+
+// Walks both trees in parallel, skipping subtrees with equal hashes, and collects the cells that differ themselves
+pub(crate) fn diff_cells(a: &Cell, b: &Cell, first: bool) -> Vec<PyCellDiff> {
+    let mut diffs = Vec::new();
+    let mut stack = vec!((a.clone(), b.clone(), Vec::new()));
+    while let Some((a, b, path)) = stack.pop() {
+        if a.repr_hash() == b.repr_hash() {
+            continue
+        }
+        let bit_offset = first_different_bit(&a, &b);
+        if bit_offset.is_some() || a.references_count() != b.references_count() || a.cell_type() != b.cell_type() {
+            diffs.push(PyCellDiff { path: path.clone(), bit_offset, a: PyCell::new(a.clone()), b: PyCell::new(b.clone()) });
+            if first {
+                break
+            }
+        }
+        let refs = a.references_count().min(b.references_count());
+        for i in (0..refs).rev() {
+            let mut child_path = path.clone();
+            child_path.push(i);
+            stack.push((a.reference(i).unwrap(), b.reference(i).unwrap(), child_path));
+        }
+    }
+    diffs
+}
@@ -0,0 +1,199 @@
+use std::iter::Peekable;
+use crate::{err, PyCell, PySlice, runtime_err, utils::hash_to_int};
+use num_bigint::BigUint;
+use pyo3::prelude::*;
+use ton_types::{Cell, SliceData};
+
+// Instruction as it appears in the disassembler output, along with its nested code blocks
+#[derive(Debug, PartialEq)]
+pub(crate) struct Node {
+    pub(crate) text: String,
+    pub(crate) blocks: Vec<Vec<Node>>,
+}
+
+pub(crate) fn parse_nodes<'a, I: Iterator<Item = &'a str>>(lines: &mut Peekable<I>) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue
+        }
+        if line == "}" {
+            break
+        }
+        let Some(text) = line.strip_suffix('{') else {
+            nodes.push(Node { text: line.to_string(), blocks: Vec::new() });
+            continue
+        };
+        let mut blocks = vec!(parse_nodes(lines));
+        while lines.peek().map(|line| line.trim()) == Some("{") {
+            lines.next();
+            blocks.push(parse_nodes(lines));
+        }
+        nodes.push(Node { text: text.trim_end().to_string(), blocks });
+    }
+    nodes
+}
+
+pub(crate) fn render_nodes(nodes: &[Node], indent: usize) -> String {
+    nodes.iter().map(|node| render_node(node, indent)).collect::<Vec<_>>().join("\n")
+}
+
+fn render_node(node: &Node, indent: usize) -> String {
+    let tab = "  ".repeat(indent);
+    let mut text = format!("{}{}", tab, node.text);
+    for block in node.blocks.iter() {
+        text += &format!(" {{\n{}\n{}}}", render_nodes(block, indent + 1), tab);
+    }
+    text
+}
+
+#[derive(Clone)]
+#[pyclass(get_all, name = "Instruction")]
+pub(crate) struct PyInstruction {
+    mnemonic: String,
+    operands: Vec<String>,
+    cell_hash: BigUint,
+    offset: usize,
+    length: usize,
+    refs: usize,
+    blocks: Vec<Vec<PyInstruction>>,
+}
+
+#[pymethods]
+impl PyInstruction {
+    fn __str__(&self) -> String {
+        if self.operands.is_empty() {
+            self.mnemonic.clone()
+        } else {
+            format!("{} {}", self.mnemonic, self.operands.join(", "))
+        }
+    }
+}
+
+// Code made of the bits of the slice past the skipped ones and the given references, as a separate cell
+fn code_cell(code: &SliceData, skip: usize, bits: usize, refs: &[Cell]) -> PyResult<SliceData> {
+    let mut code = code.clone();
+    code.move_by(skip).map_err(runtime_err)?;
+    let mut builder = code.get_next_slice(bits).map_err(runtime_err)?.as_builder();
+    for cell in refs {
+        builder.checked_append_reference(cell.clone()).map_err(runtime_err)?;
+    }
+    SliceData::load_cell(builder.into_cell().map_err(runtime_err)?).map_err(runtime_err)
+}
+
+// Tells whether the disassembler decodes the code into exactly the given nodes
+fn decodes_to(code: &SliceData, nodes: &[Node]) -> bool {
+    match disasm_text(code) {
+        Ok(text) => parse_nodes(&mut text.lines().peekable()) == nodes,
+        Err(_) => false,
+    }
+}
+
+// Finds the bits and references taken by the instruction at the start of the code: instructions are
+// prefix codes, so the shortest prefix the disassembler decodes into the node is the instruction itself
+fn instruction_size(code: &SliceData, node: &Node) -> PyResult<(usize, usize)> {
+    let nodes = std::slice::from_ref(node);
+    let cells = (0..code.remaining_references().min(4))
+        .map(|index| code.reference(index))
+        .collect::<ton_types::Result<Vec<_>>>()
+        .map_err(runtime_err)?;
+    for bits in 1..=code.remaining_bits() {
+        for refs in 0..=cells.len() {
+            if decodes_to(&code_cell(code, 0, bits, &cells[..refs])?, nodes) {
+                return Ok((bits, refs))
+            }
+        }
+    }
+    err!("failed to decode instruction {} at offset {}", node.text, code.pos())
+}
+
+// Locates the nodes in the code, taking the size of each instruction from the disassembler decoding,
+// so that any valid encoding is accepted, not only the one produced by the assembler
+fn locate(nodes: &[Node], mut code: SliceData) -> PyResult<Vec<PyInstruction>> {
+    let mut instructions = Vec::new();
+    for node in nodes {
+        if code.remaining_bits() == 0 && code.remaining_references() == 1 {
+            // implicit jump to the continuation of the code
+            let next = code.reference(0).map_err(runtime_err)?;
+            code = SliceData::load_cell(next).map_err(runtime_err)?;
+        }
+        let (length, refs) = instruction_size(&code, node)?;
+        let start = code.clone();
+        code.move_by(length).map_err(runtime_err)?;
+        let mut cells = Vec::new();
+        for _ in 0..refs {
+            cells.push(Some(code.checked_drain_reference().map_err(runtime_err)?));
+        }
+        // each block is either the body of one of the references or is inlined at the end of the instruction
+        let mut blocks = Vec::new();
+        let mut inlined = false;
+        for block in node.blocks.iter() {
+            let referenced = cells.iter().position(|cell| cell.as_ref().is_some_and(|cell|
+                SliceData::load_cell(cell.clone()).is_ok_and(|body| decodes_to(&body, block))));
+            let body = if let Some(index) = referenced {
+                let cell = cells[index].take().unwrap_or_default();
+                Some(SliceData::load_cell(cell).map_err(runtime_err)?)
+            } else if !inlined {
+                let rest = cells.iter().flatten().cloned().collect::<Vec<_>>();
+                let mut body = None;
+                for skip in 1..length {
+                    if decodes_to(&code_cell(&start, skip, length - skip, &rest)?, block) {
+                        let mut inline = start.clone();
+                        inline.move_by(skip).map_err(runtime_err)?;
+                        body = Some(inline);
+                        inlined = true;
+                        break
+                    }
+                }
+                body
+            } else {
+                None
+            };
+            match body {
+                Some(body) => blocks.push(locate(block, body)?),
+                None => return err!("unsupported layout of instruction {}", node.text),
+            }
+        }
+        let (mnemonic, operands) = match node.text.split_once(char::is_whitespace) {
+            Some((mnemonic, operands)) => (mnemonic, operands.split(',').map(|op| op.trim().to_string()).collect()),
+            None => (node.text.as_str(), Vec::new()),
+        };
+        instructions.push(PyInstruction {
+            mnemonic: mnemonic.to_string(),
+            operands,
+            cell_hash: hash_to_int(&start.cell().repr_hash()),
+            offset: start.pos(),
+            length,
+            refs,
+            blocks,
+        });
+    }
+    Ok(instructions)
+}
+
+fn code_slice(code: &PyAny) -> PyResult<SliceData> {
+    if let Ok(cell) = code.extract::<PyCell>() {
+        SliceData::load_cell(cell.cell).map_err(runtime_err)
+    } else {
+        Ok(code.extract::<PySlice>()?.slice)
+    }
+}
+
+fn disasm_text(code: &SliceData) -> PyResult<String> {
+    ton_labs_assembler::disasm::disasm_ex(&mut code.clone(), false).map_err(runtime_err)
+}
+
+#[pyfunction]
+pub(crate) fn disassemble(code: &PyAny) -> PyResult<String> {
+    let code = code_slice(code)?;
+    let text = disasm_text(&code)?;
+    Ok(render_nodes(&parse_nodes(&mut text.lines().peekable()), 0))
+}
+
+#[pyfunction]
+pub(crate) fn disassemble_instructions(code: &PyAny) -> PyResult<Vec<PyInstruction>> {
+    let code = code_slice(code)?;
+    let text = disasm_text(&code)?;
+    locate(&parse_nodes(&mut text.lines().peekable()), code)
+}
@@ -0,0 +1,91 @@
+use crate::{err, runtime_err};
+use pyo3::prelude::*;
+use ton_types::{BuilderData, Cell, CellType, IBitstring, LevelMask, UInt256};
+
+pub(crate) fn cell_type_name(cell_type: CellType) -> &'static str {
+    match cell_type {
+        CellType::Ordinary         => "ordinary",
+        CellType::PrunedBranch     => "pruned_branch",
+        CellType::LibraryReference => "library",
+        CellType::MerkleProof      => "merkle_proof",
+        CellType::MerkleUpdate     => "merkle_update",
+        _                          => "unknown",
+    }
+}
+
+// The type of an exotic cell is defined by the first byte of its data
+pub(crate) fn exotic_cell(mut builder: BuilderData) -> PyResult<Cell> {
+    let data = builder.data();
+    if builder.length_in_bits() < 8 {
+        return err!("exotic cell must contain a type byte")
+    }
+    let cell_type = match data[0] {
+        1 => CellType::PrunedBranch,
+        2 => CellType::LibraryReference,
+        3 => CellType::MerkleProof,
+        4 => CellType::MerkleUpdate,
+        tag => return err!("unknown exotic cell type {}", tag),
+    };
+    if cell_type == CellType::PrunedBranch {
+        if builder.length_in_bits() < 16 {
+            return err!("pruned branch cell must contain a level mask")
+        }
+        let level_mask = LevelMask::with_mask(data[1]);
+        builder.set_level_mask(level_mask);
+    }
+    builder.set_type(cell_type);
+    builder.into_cell().map_err(runtime_err)
+}
+
+pub(crate) fn pruned_branch(cell: &Cell, merkle_depth: u8) -> PyResult<Cell> {
+    if merkle_depth > 2 {
+        return err!("merkle depth {} is too big", merkle_depth)
+    }
+    let cell_mask = cell.level_mask().mask();
+    let cell_level = cell.level_mask().level();
+    if merkle_depth < cell_level {
+        return err!("merkle depth {} is below the cell level {}", merkle_depth, cell_level)
+    }
+    // one hash and depth is stored for each significant level of the pruned cell
+    let levels = (0..=cell_level as usize)
+        .filter(|level| *level == 0 || cell_mask & (1 << (level - 1)) != 0)
+        .collect::<Vec<_>>();
+    let mut builder = BuilderData::new();
+    builder.append_u8(1).map_err(runtime_err)?;
+    builder.append_u8(cell_mask | (1 << merkle_depth)).map_err(runtime_err)?;
+    for level in &levels {
+        builder.append_raw(cell.hash(*level).as_slice(), 256).map_err(runtime_err)?;
+    }
+    for level in &levels {
+        builder.append_u16(cell.depth(*level)).map_err(runtime_err)?;
+    }
+    exotic_cell(builder)
+}
+
+pub(crate) fn library_cell(hash: &UInt256) -> PyResult<Cell> {
+    let mut builder = BuilderData::new();
+    builder.append_u8(2).map_err(runtime_err)?;
+    builder.append_raw(hash.as_slice(), 256).map_err(runtime_err)?;
+    exotic_cell(builder)
+}
+
+pub(crate) fn merkle_proof(cell: &Cell) -> PyResult<Cell> {
+    let mut builder = BuilderData::new();
+    builder.append_u8(3).map_err(runtime_err)?;
+    builder.append_raw(cell.hash(0).as_slice(), 256).map_err(runtime_err)?;
+    builder.append_u16(cell.depth(0)).map_err(runtime_err)?;
+    builder.checked_append_reference(cell.clone()).map_err(runtime_err)?;
+    exotic_cell(builder)
+}
+
+pub(crate) fn merkle_update(old: &Cell, new: &Cell) -> PyResult<Cell> {
+    let mut builder = BuilderData::new();
+    builder.append_u8(4).map_err(runtime_err)?;
+    builder.append_raw(old.hash(0).as_slice(), 256).map_err(runtime_err)?;
+    builder.append_raw(new.hash(0).as_slice(), 256).map_err(runtime_err)?;
+    builder.append_u16(old.depth(0)).map_err(runtime_err)?;
+    builder.append_u16(new.depth(0)).map_err(runtime_err)?;
+    builder.checked_append_reference(old.clone()).map_err(runtime_err)?;
+    builder.checked_append_reference(new.clone()).map_err(runtime_err)?;
+    exotic_cell(builder)
+}
@@ -0,0 +1,280 @@
+use std::{fs::File, sync::{Arc, OnceLock}};
+use crate::{boc::{cell_data_range, read_ref, BocLayout}, err, utils::{boc_err, BocError, PlaygroundError}};
+use memmap2::Mmap;
+use pyo3::prelude::*;
+use ton_types::{BuilderData, Cell, CellData, CellImpl, CellType, LevelMask, Sha256, UInt256};
+
+// Hashes and depths of a cell for each of the levels 0..=3
+struct CellHashes {
+    hashes: [UInt256; 4],
+    depths: [u16; 4],
+}
+
+const PRUNED_BRANCH_TYPE: u8 = 1;
+const LIBRARY_REFERENCE_TYPE: u8 = 2;
+const MERKLE_PROOF_TYPE: u8 = 3;
+const MERKLE_UPDATE_TYPE: u8 = 4;
+
+// Checks the layout of an exotic cell and returns its expected level mask
+fn exotic_level_mask(data: &[u8], aligned: bool, refs: usize, children_mask: u8) -> Result<u8, String> {
+    if !aligned || data.is_empty() {
+        return Err("exotic cell data must be a non-empty whole number of bytes".to_string())
+    }
+    let (expected_refs, expected_len, mask) = match data[0] {
+        PRUNED_BRANCH_TYPE => {
+            let mask = data.get(1).copied().unwrap_or_default();
+            if mask == 0 || mask > 7 {
+                return Err(format!("pruned branch has invalid level mask {}", mask))
+            }
+            (0, 2 + mask.count_ones() as usize * (32 + 2), mask)
+        }
+        LIBRARY_REFERENCE_TYPE => (0, 1 + 32, 0),
+        MERKLE_PROOF_TYPE => (1, 1 + 32 + 2, children_mask >> 1),
+        MERKLE_UPDATE_TYPE => (2, 1 + 2 * (32 + 2), children_mask >> 1),
+        typ => return Err(format!("unknown exotic cell type {}", typ)),
+    };
+    if refs != expected_refs || data.len() != expected_len {
+        return Err(format!("exotic cell of type {} has {} bytes and {} references", data[0], data.len(), refs))
+    }
+    Ok(mask)
+}
+
+// Memory-mapped bag of cells with the offsets of all its cells
+struct LazyBoc {
+    data: Mmap,
+    layout: BocLayout,
+    // hashes are kept per cell index, so that subtrees shared by several parents are hashed once
+    hashes: Vec<OnceLock<Box<CellHashes>>>,
+}
+
+impl LazyBoc {
+    fn map(path: &str) -> PyResult<Self> {
+        let file = File::open(path)
+            .map_err(|err| PlaygroundError::new_err(format!("failed to open {}: {}", path, err)))?;
+        // the file is expected to stay unchanged while the cells are in use
+        let data = unsafe { Mmap::map(&file) }
+            .map_err(|err| PlaygroundError::new_err(format!("failed to map {}: {}", path, err)))?;
+        let layout = BocLayout::parse(&data)?;
+        if layout.absent != 0 {
+            return err!("absent cells are not supported")
+        }
+        // cells are checked once here, so that they can be hashed and materialized later without errors;
+        // children follow their parents, so their level masks are known when the parents are checked
+        let mut masks = vec!(0u8; layout.cells());
+        for i in (0..layout.cells()).rev() {
+            let raw = layout.raw(&data, i);
+            let refs = (raw[0] & 7) as usize;
+            if refs > 4 {
+                return Err(BocError::new_err(format!("cell {} has {} references", i, refs)))
+            }
+            let (start, size) = cell_data_range(raw);
+            let aligned = raw[1] & 1 == 0;
+            if !aligned && raw[start + size - 1] == 0 {
+                return Err(BocError::new_err(format!("cell {} has no completion tag", i)))
+            }
+            let children_mask = layout.references(&data, i).iter().fold(0, |mask, child| mask | masks[*child]);
+            let expected = if raw[0] & 8 == 0 {
+                children_mask
+            } else {
+                exotic_level_mask(&raw[start..start + size], aligned, refs, children_mask)
+                    .map_err(|msg| BocError::new_err(format!("cell {}: {}", i, msg)))?
+            };
+            let mask = raw[0] >> 5;
+            if mask != expected {
+                return Err(BocError::new_err(format!("cell {} has level mask {} instead of {}", i, mask, expected)))
+            }
+            masks[i] = mask;
+        }
+        let hashes = (0..layout.cells()).map(|_| OnceLock::new()).collect();
+        Ok(Self { data, layout, hashes })
+    }
+    fn raw(&self, index: usize) -> &[u8] {
+        self.layout.raw(&self.data, index)
+    }
+    fn hashes(&self, index: usize) -> &CellHashes {
+        self.hashes[index].get_or_init(|| Box::new(self.compute_hashes(index)))
+    }
+    // Computes the hashes from the raw data of the cell and the cached hashes of its children,
+    // following the representation hash definition for each significant level
+    fn compute_hashes(&self, index: usize) -> CellHashes {
+        let raw = self.raw(index);
+        let mask = raw[0] >> 5;
+        let count = mask.count_ones() as usize + 1;
+        let (start, size) = cell_data_range(raw);
+        let data = &raw[start..start + size];
+        let exotic_type = if raw[0] & 8 != 0 { data[0] } else { 0 };
+        let hash_index = |level: usize| (mask & ((1 << level) - 1)).count_ones() as usize;
+        let mut hashes = [UInt256::default(), UInt256::default(), UInt256::default(), UInt256::default()];
+        let mut depths = [0; 4];
+        // the stored hashes of the significant levels are followed by their depths
+        if raw[0] & 16 != 0 && raw[0] & 8 == 0 {
+            for level in 0..4 {
+                let i = hash_index(level);
+                hashes[level] = UInt256::from_slice(&raw[2 + i * 32..2 + (i + 1) * 32]);
+                let pos = 2 + count * 32 + i * 2;
+                depths[level] = u16::from_be_bytes([raw[pos], raw[pos + 1]]);
+            }
+            return CellHashes { hashes, depths }
+        }
+        let children = self.layout.references(&self.data, index);
+        let merkle = matches!(exotic_type, MERKLE_PROOF_TYPE | MERKLE_UPDATE_TYPE) as usize;
+        // a pruned branch computes only its representation hash, the lower ones are stored in its data
+        let pruned = exotic_type == PRUNED_BRANCH_TYPE;
+        let cell_level = 8 - mask.leading_zeros() as usize;
+        let mut computed: Vec<(UInt256, u16)> = Vec::new();
+        for level in 0..=cell_level {
+            let significant = level == 0 || mask & (1 << (level - 1)) != 0;
+            if !significant || (pruned && level != cell_level) {
+                continue
+            }
+            let mut hasher = Sha256::new();
+            hasher.update([raw[0] & 15 | (mask & ((1 << level) - 1)) << 5, raw[1]]);
+            match computed.last() {
+                Some((hash, _)) => hasher.update(hash.as_slice()),
+                None => hasher.update(data),
+            }
+            let child_level = (level + merkle).min(3);
+            let mut depth = 0;
+            for child in &children {
+                let child_depth = self.hashes(*child).depths[child_level];
+                hasher.update(child_depth.to_be_bytes());
+                depth = depth.max(child_depth + 1);
+            }
+            for child in &children {
+                hasher.update(self.hashes(*child).hashes[child_level].as_slice());
+            }
+            computed.push((UInt256::from(hasher.finalize()), depth));
+        }
+        for level in 0..4 {
+            let i = hash_index(level);
+            if pruned && i + 1 < count {
+                hashes[level] = UInt256::from_slice(&data[2 + i * 32..2 + (i + 1) * 32]);
+                let pos = 2 + (count - 1) * 32 + i * 2;
+                depths[level] = u16::from_be_bytes([data[pos], data[pos + 1]]);
+            } else {
+                let (hash, depth) = &computed[if pruned { 0 } else { i }];
+                hashes[level] = hash.clone();
+                depths[level] = *depth;
+            }
+        }
+        CellHashes { hashes, depths }
+    }
+}
+
+pub(crate) struct LazyCell {
+    boc: Arc<LazyBoc>,
+    index: usize,
+    children: OnceLock<Vec<Cell>>,
+    // copy of the cell referencing the lazy children, built only on a request of its raw data
+    cell: OnceLock<Cell>,
+}
+
+impl LazyCell {
+    fn load(boc: Arc<LazyBoc>, index: usize) -> Cell {
+        Cell::with_cell_impl(Self { boc, index, children: OnceLock::new(), cell: OnceLock::new() })
+    }
+    fn raw(&self) -> &[u8] {
+        self.boc.raw(self.index)
+    }
+    fn data_range(&self) -> (usize, usize) {
+        let (start, size) = cell_data_range(self.raw());
+        (start, start + size)
+    }
+    fn children(&self) -> &Vec<Cell> {
+        self.children.get_or_init(|| {
+            let raw = self.raw();
+            let (_, data_end) = self.data_range();
+            (0..(raw[0] & 7) as usize)
+                .map(|i| {
+                    let ref_size = self.boc.layout.ref_size;
+                    let index = read_ref(&raw[data_end + i * ref_size..], ref_size);
+                    LazyCell::load(self.boc.clone(), index)
+                })
+                .collect()
+        })
+    }
+    fn hashes(&self) -> &CellHashes {
+        self.boc.hashes(self.index)
+    }
+    fn materialized(&self) -> &Cell {
+        self.cell.get_or_init(|| {
+            let mut builder = BuilderData::with_raw(self.data().to_vec(), self.bit_length())
+                .expect("cell data is checked on loading");
+            for child in self.children() {
+                builder.checked_append_reference(child.clone())
+                    .expect("cell references are checked on loading");
+            }
+            builder.set_type(self.cell_type());
+            builder.set_level_mask(self.level_mask());
+            builder.into_cell().expect("cell layout is checked on loading")
+        })
+    }
+}
+
+impl CellImpl for LazyCell {
+    fn data(&self) -> &[u8] {
+        let (start, end) = self.data_range();
+        &self.raw()[start..end]
+    }
+    fn raw_data(&self) -> ton_types::Result<&[u8]> {
+        self.materialized().raw_data()
+    }
+    fn cell_data(&self) -> &CellData {
+        self.materialized().cell_data()
+    }
+    fn bit_length(&self) -> usize {
+        let d2 = self.raw()[1] as usize;
+        let data = self.data();
+        match data.last() {
+            Some(last) if d2 & 1 == 1 => data.len() * 8 - last.trailing_zeros() as usize - 1,
+            _ => data.len() * 8,
+        }
+    }
+    fn references_count(&self) -> usize {
+        (self.raw()[0] & 7) as usize
+    }
+    fn reference(&self, index: usize) -> ton_types::Result<Cell> {
+        match self.children().get(index) {
+            Some(cell) => Ok(cell.clone()),
+            None => ton_types::fail!("reference {} is out of range", index),
+        }
+    }
+    fn cell_type(&self) -> CellType {
+        if self.raw()[0] & 8 == 0 {
+            return CellType::Ordinary
+        }
+        match self.data().first() {
+            Some(&PRUNED_BRANCH_TYPE) => CellType::PrunedBranch,
+            Some(&LIBRARY_REFERENCE_TYPE) => CellType::LibraryReference,
+            Some(&MERKLE_PROOF_TYPE) => CellType::MerkleProof,
+            Some(&MERKLE_UPDATE_TYPE) => CellType::MerkleUpdate,
+            _ => CellType::Unknown,
+        }
+    }
+    fn level_mask(&self) -> LevelMask {
+        LevelMask::with_mask(self.raw()[0] >> 5)
+    }
+    fn hash(&self, index: usize) -> UInt256 {
+        self.hashes().hashes[index.min(3)].clone()
+    }
+    fn depth(&self, index: usize) -> u16 {
+        self.hashes().depths[index.min(3)]
+    }
+    fn store_hashes(&self) -> bool {
+        false
+    }
+}
+
+pub(crate) fn read_file(path: &str, lazy: bool) -> PyResult<Cell> {
+    if !lazy {
+        let bytes = std::fs::read(path)
+            .map_err(|err| PlaygroundError::new_err(format!("failed to read {}: {}", path, err)))?;
+        return ton_types::read_single_root_boc(bytes).map_err(boc_err)
+    }
+    let boc = Arc::new(LazyBoc::map(path)?);
+    if boc.layout.roots.len() != 1 {
+        return err!("boc must contain exactly one root, found {}", boc.layout.roots.len())
+    }
+    let root = boc.layout.roots[0];
+    Ok(LazyCell::load(boc, root))
+}
@@ -1,22 +1,47 @@
+mod address;
+mod assembler;
+mod boc;
 mod continuations;
+mod coverage;
 mod crypto;
+mod diff;
+mod disasm;
+mod exotic;
+mod lazy;
+mod merkle;
+mod parse;
+mod profile;
+mod snake;
 mod tests;
+mod trace;
 mod utils;
 mod vm;
 
 use std::collections::HashSet;
+use address::*;
+use assembler::*;
+use boc::*;
 use continuations::*;
+use coverage::*;
 use crypto::*;
+use diff::*;
+use disasm::*;
+use exotic::*;
+use lazy::*;
+use merkle::*;
+use parse::*;
+use profile::*;
+use snake::*;
+use trace::*;
 use utils::*;
 use vm::*;
 use num_bigint::{BigInt, BigUint, Sign};
 use pyo3::{
     prelude::*,
     basic::CompareOp,
-    exceptions::PyRuntimeError,
     types::{PyBytes, PyTuple},
 };
-use ton_types::{BuilderData, Cell, HashmapE, HashmapType, SliceData, IBitstring};
+use ton_types::{BuilderData, Cell, CellType, HashmapE, HashmapType, SliceData, IBitstring};
 
 #[pyclass(name = "Cell")]
 #[derive(Clone)]
@@ -28,62 +53,116 @@ impl PyCell {
     fn new(cell: Cell) -> Self {
         Self { cell }
     }
-}
-
-#[pymethods]
-impl PyCell {
-    #[new]
-    #[pyo3(signature = (bitstring, *args))]
-    fn create(bitstring: String, args: &PyTuple) -> PyResult<Self> {
+    fn builder(bitstring: String, args: &PyTuple) -> PyResult<BuilderData> {
         if args.len() > 4 {
-            return err!("cell can't contain more than 4 references")
+            return Err(CellOverflowError::new_err("cell can't contain more than 4 references"))
         }
         let slice = SliceData::from_string(&bitstring)
-            .map_err(|_| PyRuntimeError::new_err(format!("invalid bitstring \"{}\"", bitstring)))?;
+            .map_err(|_| InvalidBitstringError::new_err(format!("invalid bitstring \"{}\"", bitstring)))?;
         let mut b = slice.as_builder();
         for arg in args.iter() {
             let cell = arg.extract::<PyCell>()?;
             b.checked_append_reference(cell.cell).map_err(runtime_err)?;
         }
-        let cell = b.into_cell().map_err(runtime_err)?;
+        Ok(b)
+    }
+}
+
+#[pymethods]
+impl PyCell {
+    #[new]
+    #[pyo3(signature = (bitstring, *args))]
+    fn create(bitstring: String, args: &PyTuple) -> PyResult<Self> {
+        let cell = Self::builder(bitstring, args)?.into_cell().map_err(runtime_err)?;
         Ok(Self::new(cell))
     }
     #[staticmethod]
     fn empty() -> Self {
         Self::new(Cell::default())
     }
+    #[staticmethod]
+    #[pyo3(signature = (bitstring, *args))]
+    fn exotic(bitstring: String, args: &PyTuple) -> PyResult<Self> {
+        exotic_cell(Self::builder(bitstring, args)?).map(Self::new)
+    }
+    #[staticmethod]
+    #[pyo3(signature = (cell, merkle_depth = 0))]
+    fn pruned_branch(cell: PyCell, merkle_depth: u8) -> PyResult<Self> {
+        pruned_branch(&cell.cell, merkle_depth).map(Self::new)
+    }
+    #[staticmethod]
+    fn library(hash: BigUint) -> PyResult<Self> {
+        library_cell(&hash_from_int(&hash)?).map(Self::new)
+    }
+    #[staticmethod]
+    fn merkle_proof(cell: PyCell) -> PyResult<Self> {
+        merkle_proof(&cell.cell).map(Self::new)
+    }
+    #[staticmethod]
+    fn merkle_update(old: PyCell, new: PyCell) -> PyResult<Self> {
+        merkle_update(&old.cell, &new.cell).map(Self::new)
+    }
+    fn make_merkle_proof(&self, py: Python<'_>, visited: &PyAny) -> PyResult<PyMerkleProof> {
+        make_merkle_proof(py, &self.cell, visited).map(PyMerkleProof::new)
+    }
+    fn is_exotic(&self) -> bool {
+        self.cell.cell_type() != CellType::Ordinary
+    }
+    fn cell_type(&self) -> &'static str {
+        cell_type_name(self.cell.cell_type())
+    }
     fn reference(&self, index: usize) -> PyResult<Self> {
         self.cell.reference(index)
             .map(|cell| Self::new(cell))
             .map_err(runtime_err)
     }
-    fn write<'a>(&'a self, py: Python<'a>, flags: usize) -> PyResult<&PyBytes> {
+    #[pyo3(signature = (flags, fift = false))]
+    fn write<'a>(&'a self, py: Python<'a>, flags: usize, fift: bool) -> PyResult<&PyBytes> {
         if flags > 3 {
             return err!("flags {} is not supported", flags)
         }
         let include_index = flags & 1 == 1;
         let include_crc = flags & 2 == 2;
-        let writer = ton_types::BocWriter::with_root(&self.cell).map_err(runtime_err)?;
+        if fift {
+            let bytes = write_fift(&[self.cell.clone()], include_index, include_crc)?;
+            return Ok(PyBytes::new(py, &bytes))
+        }
+        let writer = ton_types::BocWriter::with_root(&self.cell).map_err(boc_err)?;
         let mut bytes = Vec::new();
-        writer.write_ex(&mut bytes, include_index, include_crc, None, None).map_err(runtime_err)?;
+        writer.write_ex(&mut bytes, include_index, include_crc, None, None).map_err(boc_err)?;
         Ok(PyBytes::new(py, &bytes))
     }
     #[staticmethod]
     fn read(bytes: Vec<u8>) -> PyResult<Self> {
         ton_types::read_single_root_boc(bytes)
             .map(|cell| Self::new(cell))
-            .map_err(runtime_err)
+            .map_err(boc_err)
+    }
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        parse_cell(text).map(Self::new)
+    }
+    #[staticmethod]
+    #[pyo3(signature = (path, lazy = true))]
+    fn read_file(path: &str, lazy: bool) -> PyResult<Self> {
+        read_file(path, lazy).map(Self::new)
     }
     fn repr_hash(&self) -> BigUint {
         let hash = self.cell.repr_hash();
         BigUint::from_bytes_be(hash.as_slice())
     }
+    fn hash(&self, index: usize) -> BigUint {
+        hash_to_int(&self.cell.hash(index))
+    }
     fn repr_depth(&self) -> usize {
         self.cell.repr_depth() as usize
     }
     fn level(&self) -> usize {
         self.cell.level() as usize
     }
+    fn level_mask(&self) -> u8 {
+        self.cell.level_mask().mask()
+    }
     fn depth(&self, index: usize) -> usize {
         self.cell.depth(index) as usize
     }
@@ -105,6 +184,20 @@ impl PyCell {
         }
         Ok(unique_count)
     }
+    #[staticmethod]
+    #[pyo3(signature = (a, b, first = false))]
+    fn diff(a: Self, b: Self, first: bool) -> Vec<PyCellDiff> {
+        diff_cells(&a.cell, &b.cell, first)
+    }
+    #[pyo3(signature = (format = "playground"))]
+    fn dump(&self, format: &str) -> PyResult<String> {
+        match format {
+            "playground" => Ok(dump_cell(self.cell.clone())),
+            "fift" => Ok(dump_cell_fift(self.cell.clone())),
+            "tree" => Ok(dump_cell_tree(self.cell.clone())),
+            _ => err!("unknown dump format \"{}\"", format),
+        }
+    }
     fn __str__(&self) -> PyResult<String> {
         Ok(dump_cell(self.cell.clone()))
     }
@@ -127,6 +220,55 @@ impl PySlice {
     fn new(slice: SliceData) -> Self {
         Self { slice }
     }
+    fn check_remaining(&self, bits: usize, refs: usize) -> PyResult<()> {
+        if bits > self.slice.remaining_bits() || refs > self.slice.remaining_references() {
+            return Err(CellUnderflowError::new_err(format!(
+                "slice of {} bits and {} refs is too short for {} bits and {} refs",
+                self.slice.remaining_bits(), self.slice.remaining_references(), bits, refs
+            )))
+        }
+        Ok(())
+    }
+    // Slice of the bits and refs in the given ranges of this slice
+    fn sub_slice(&self, bits_offset: usize, bits: usize, refs_offset: usize, refs: usize) -> PyResult<SliceData> {
+        self.check_remaining(bits_offset + bits, refs_offset + refs)?;
+        let mut data = self.slice.clone();
+        data.move_by(bits_offset).map_err(runtime_err)?;
+        let mut builder = data.get_next_slice(bits).map_err(runtime_err)?.as_builder();
+        for i in refs_offset..refs_offset + refs {
+            let cell = self.slice.reference(i).map_err(runtime_err)?;
+            builder.checked_append_reference(cell).map_err(runtime_err)?;
+        }
+        SliceData::load_cell(builder.into_cell().map_err(runtime_err)?).map_err(runtime_err)
+    }
+    fn peek_bytes(&self, bits: usize) -> PyResult<Vec<u8>> {
+        self.check_remaining(bits, 0)?;
+        self.slice.clone().get_next_bits(bits).map_err(runtime_err)
+    }
+    fn load_var_uint(&mut self, len_bits: usize) -> PyResult<BigUint> {
+        let len = self.slice.get_next_int(len_bits).map_err(runtime_err)? as usize;
+        if len == 0 {
+            return Ok(BigUint::default())
+        }
+        let bytes = self.slice.get_next_bits(len * 8).map_err(runtime_err)?;
+        Ok(BigUint::from_bytes_be(&bytes))
+    }
+    fn take_bytes_chain(&mut self) -> PyResult<Vec<u8>> {
+        let (data, linked) = load_bytes_chain(&self.slice)?;
+        // the data and the reference linking the chain, if any, are consumed
+        let (bits, refs) = self.bits_and_refs();
+        self.slice = self.sub_slice(bits, 0, 0, if linked { refs - 1 } else { refs })?;
+        Ok(data)
+    }
+    fn prefix_len(&self, prefix: &PySlice) -> Option<usize> {
+        let bits = prefix.slice.remaining_bits();
+        if bits > self.slice.remaining_bits() {
+            return None
+        }
+        let ours = self.slice.clone().get_next_bits(bits).ok()?;
+        let theirs = prefix.slice.clone().get_next_bits(bits).ok()?;
+        if ours == theirs { Some(bits) } else { None }
+    }
 }
 
 #[pymethods]
@@ -170,6 +312,87 @@ impl PySlice {
         self.slice.move_by(bits)
             .map_err(runtime_err)
     }
+    fn peek_i(&self, bits: usize) -> PyResult<BigInt> {
+        signed_int_deserialize(&self.peek_bytes(bits)?, bits)
+    }
+    fn peek_u(&self, bits: usize) -> PyResult<BigUint> {
+        unsigned_int_deserialize(&self.peek_bytes(bits)?, bits)
+    }
+    fn peek_bits(&self, bits: usize) -> PyResult<Self> {
+        self.sub_slice(0, bits, 0, 0).map(Self::new)
+    }
+    fn begins_with(&self, prefix: PySlice) -> bool {
+        self.prefix_len(&prefix).is_some()
+    }
+    fn try_skip_prefix(&mut self, prefix: PySlice) -> PyResult<bool> {
+        match self.prefix_len(&prefix) {
+            Some(bits) => self.skip(bits).map(|_| true),
+            None => Ok(false),
+        }
+    }
+    #[pyo3(signature = (bits, refs = 0))]
+    fn cut_first(&self, bits: usize, refs: usize) -> PyResult<Self> {
+        self.sub_slice(0, bits, 0, refs).map(Self::new)
+    }
+    #[pyo3(signature = (bits, refs = 0))]
+    fn cut_last(&self, bits: usize, refs: usize) -> PyResult<Self> {
+        self.check_remaining(bits, refs)?;
+        let (remaining_bits, remaining_refs) = self.bits_and_refs();
+        self.sub_slice(remaining_bits - bits, bits, remaining_refs - refs, refs).map(Self::new)
+    }
+    #[pyo3(signature = (bits, refs = 0))]
+    fn skip_last(&mut self, bits: usize, refs: usize) -> PyResult<()> {
+        self.check_remaining(bits, refs)?;
+        let (remaining_bits, remaining_refs) = self.bits_and_refs();
+        self.slice = self.sub_slice(0, remaining_bits - bits, 0, remaining_refs - refs)?;
+        Ok(())
+    }
+    fn var_uint(&mut self, len_bits: usize) -> PyResult<BigUint> {
+        self.load_var_uint(len_bits)
+    }
+    fn bytes_chain(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        let data = self.take_bytes_chain()?;
+        Ok(PyBytes::new(py, &data).into())
+    }
+    fn string(&mut self) -> PyResult<String> {
+        let data = self.take_bytes_chain()?;
+        String::from_utf8(data).or_else(|_| err!("string is not valid UTF-8"))
+    }
+    fn address(&mut self) -> PyResult<PyAddress> {
+        Address::read(&mut self.slice).map(PyAddress::new)
+    }
+    fn coins(&mut self) -> PyResult<BigUint> {
+        self.load_var_uint(4)
+    }
+    fn maybe_ref(&mut self) -> PyResult<Option<PyCell>> {
+        if !self.slice.get_next_bit().map_err(runtime_err)? {
+            return Ok(None)
+        }
+        self.r().map(Some)
+    }
+    fn either_ref(&mut self) -> PyResult<Self> {
+        if self.slice.get_next_bit().map_err(runtime_err)? {
+            return Self::create(self.r()?)
+        }
+        // the inline value takes the rest of the slice
+        let rest = self.slice.clone();
+        self.slice.move_by(self.slice.remaining_bits()).map_err(runtime_err)?;
+        for _ in 0..self.slice.remaining_references() {
+            self.slice.checked_drain_reference().map_err(runtime_err)?;
+        }
+        Ok(Self::new(rest))
+    }
+    fn bits_and_refs(&self) -> (usize, usize) {
+        (self.slice.remaining_bits(), self.slice.remaining_references())
+    }
+    #[pyo3(signature = (bits, refs = 0))]
+    fn split(&self, bits: usize, refs: usize) -> PyResult<(Self, Self)> {
+        self.check_remaining(bits, refs)?;
+        let (remaining_bits, remaining_refs) = self.bits_and_refs();
+        let head = self.sub_slice(0, bits, 0, refs)?;
+        let tail = self.sub_slice(bits, remaining_bits - bits, refs, remaining_refs - refs)?;
+        Ok((Self::new(head), Self::new(tail)))
+    }
     fn __richcmp__(&self, other: Self, op: CompareOp, py: Python<'_>) -> PyObject {
         match op {
             CompareOp::Eq => self.slice.eq(&other.slice).into_py(py),
@@ -192,6 +415,17 @@ impl PyBuilder {
     fn new(builder: BuilderData) -> Self {
         Self { builder }
     }
+    fn store_var_uint(&mut self, len_bits: usize, value: &BigUint) -> PyResult<()> {
+        let len = (value.bits() as usize).div_ceil(8);
+        if len_bits == 0 || len_bits > 8 || len >= 1 << len_bits {
+            return err!("{} doesn't fit into a variable-length integer with {}-bit length", value, len_bits)
+        }
+        self.builder.append_bits(len, len_bits).map_err(runtime_err)?;
+        if len > 0 {
+            self.builder.append_raw(&value.to_bytes_be(), len * 8).map_err(runtime_err)?;
+        }
+        Ok(())
+    }
 }
 
 #[pymethods]
@@ -227,14 +461,14 @@ impl PyBuilder {
             match digit {
                 '0' => { slf.builder.append_bit_zero().map_err(runtime_err)?; }
                 '1' => { slf.builder.append_bit_one().map_err(runtime_err)?; }
-                _ => return err!("Failed to parse binary string {}", bin)
+                _ => return Err(InvalidBitstringError::new_err(format!("Failed to parse binary string {}", bin)))
             }
         }
         Ok(slf)
     }
     fn x(mut slf: PyRefMut<Self>, bitstring: String) -> PyResult<PyRefMut<Self>> {
         let slice = SliceData::from_string(&bitstring)
-            .map_err(runtime_err)?;
+            .map_err(|_| InvalidBitstringError::new_err(format!("invalid bitstring \"{}\"", bitstring)))?;
         slf.builder.checked_append_references_and_data(&slice)
             .map_err(runtime_err)?;
         Ok(slf)
@@ -252,6 +486,61 @@ impl PyBuilder {
             .map_err(runtime_err)?;
         Ok(slf)
     }
+    fn var_uint(mut slf: PyRefMut<Self>, len_bits: usize, value: BigUint) -> PyResult<PyRefMut<Self>> {
+        slf.store_var_uint(len_bits, &value)?;
+        Ok(slf)
+    }
+    fn coins(mut slf: PyRefMut<Self>, value: BigUint) -> PyResult<PyRefMut<Self>> {
+        slf.store_var_uint(4, &value)?;
+        Ok(slf)
+    }
+    fn bytes_chain(mut slf: PyRefMut<Self>, data: Vec<u8>) -> PyResult<PyRefMut<Self>> {
+        store_bytes_chain(&mut slf.builder, &data)?;
+        Ok(slf)
+    }
+    #[pyo3(signature = (text, snake = true))]
+    fn string(mut slf: PyRefMut<Self>, text: &str, snake: bool) -> PyResult<PyRefMut<Self>> {
+        if snake {
+            store_bytes_chain(&mut slf.builder, text.as_bytes())?;
+        } else {
+            slf.builder.append_raw(text.as_bytes(), text.len() * 8).map_err(runtime_err)?;
+        }
+        Ok(slf)
+    }
+    fn address(mut slf: PyRefMut<Self>, address: PyAddress) -> PyResult<PyRefMut<Self>> {
+        address.address.write(&mut slf.builder)?;
+        Ok(slf)
+    }
+    fn maybe_ref(mut slf: PyRefMut<Self>, cell: Option<PyCell>) -> PyResult<PyRefMut<Self>> {
+        match cell {
+            Some(cell) => {
+                if !slf.builder.check_enough_refs(1) {
+                    return Err(CellOverflowError::new_err("not enough references left in the builder"))
+                }
+                slf.builder.append_bit_one().map_err(runtime_err)?;
+                slf.builder.checked_append_reference(cell.cell).map_err(runtime_err)?;
+            }
+            None => { slf.builder.append_bit_zero().map_err(runtime_err)?; }
+        }
+        Ok(slf)
+    }
+    #[pyo3(signature = (cell, as_ref = None))]
+    fn either_ref(mut slf: PyRefMut<Self>, cell: PyCell, as_ref: Option<bool>) -> PyResult<PyRefMut<Self>> {
+        let slice = SliceData::load_cell(cell.cell.clone()).map_err(runtime_err)?;
+        let fits = slf.builder.check_enough_space(1 + slice.remaining_bits()) &&
+            slf.builder.check_enough_refs(slice.remaining_references());
+        if as_ref.unwrap_or(!fits) {
+            if !slf.builder.check_enough_refs(1) {
+                return Err(CellOverflowError::new_err("not enough references left in the builder"))
+            }
+            slf.builder.append_bit_one().map_err(runtime_err)?;
+            slf.builder.checked_append_reference(cell.cell).map_err(runtime_err)?;
+        } else {
+            slf.builder.append_bit_zero().map_err(runtime_err)?;
+            slf.builder.checked_append_references_and_data(&slice).map_err(runtime_err)?;
+        }
+        Ok(slf)
+    }
     fn fits(&self, slice: PySlice, extra_bits: usize, extra_refs: usize) -> bool {
         self.builder.check_enough_space(slice.slice.remaining_bits() + extra_bits) &&
             self.builder.check_enough_refs(slice.slice.remaining_references() + extra_refs)
@@ -295,32 +584,32 @@ impl PyDictionary {
         self.map.bit_len()
     }
     fn get(&self, key: PySlice, py: Python<'_>) -> PyResult<PyObject> {
-        match self.map.get(key.slice).map_err(runtime_err)? {
+        match self.map.get(key.slice).map_err(dict_err)? {
             Some(slice) => Ok(PySlice::new(slice).into_py(py)),
             None => Ok(py.None())
         }
     }
     fn add(mut slf: PyRefMut<Self>, key: PySlice, value: PySlice) -> PyResult<PyRefMut<Self>> {
-        slf.map.set(key.slice, &value.slice).map_err(runtime_err)?;
+        slf.map.set(key.slice, &value.slice).map_err(dict_err)?;
         Ok(slf)
     }
     fn add_ref(mut slf: PyRefMut<Self>, key: PySlice, value: PyCell) -> PyResult<PyRefMut<Self>> {
-        slf.map.setref(key.slice, &value.cell).map_err(runtime_err)?;
+        slf.map.setref(key.slice, &value.cell).map_err(dict_err)?;
         Ok(slf)
     }
     fn add_kv_slice(mut slf: PyRefMut<Self>, key_bits: usize, mut slice: PySlice) -> PyResult<PyRefMut<Self>> {
         let key = slice.slice.get_next_slice(key_bits).map_err(runtime_err)?;
-        slf.map.set(key, &slice.slice).map_err(runtime_err)?;
+        slf.map.set(key, &slice.slice).map_err(dict_err)?;
         Ok(slf)
     }
     fn cell(&self) -> PyResult<PyCell> {
         self.map.data()
             .map(|cell| PyCell { cell: cell.clone() })
-            .ok_or(PyRuntimeError::new_err("empty dictionary"))
+            .ok_or(DictionaryError::new_err("empty dictionary"))
     }
     fn serialize(&self) -> PyResult<PyBuilder> {
         let mut builder = BuilderData::new();
-        self.map.write_hashmap_data(&mut builder).map_err(runtime_err)?;
+        self.map.write_hashmap_data(&mut builder).map_err(dict_err)?;
         Ok(PyBuilder::new(builder))
     }
     #[staticmethod]
@@ -334,7 +623,7 @@ impl PyDictionary {
         Ok(Self::new(map))
     }
     fn __len__(&self) -> PyResult<usize> {
-        self.map.count(usize::MAX).map_err(runtime_err)
+        self.map.count(usize::MAX).map_err(dict_err)
     }
     fn __str__(&self) -> PyResult<String> {
         match self.map.data() {
@@ -344,13 +633,6 @@ impl PyDictionary {
     }
 }
 
-#[pyfunction]
-fn assemble(code: String) -> PyResult<PyCell> {
-    let slice = ton_labs_assembler::compile_code(&code)
-        .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
-    Ok(PyCell::new(slice.cell().clone()))
-}
-
 #[derive(Clone)]
 #[pyclass(name = "NaN")]
 struct PyNaN {
@@ -379,19 +661,41 @@ impl PyNaN {
 }
 
 #[pymodule]
-fn ever_playground(_py: Python, m: &PyModule) -> PyResult<()> {
+fn ever_playground(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyNaN>()?;
     m.add_class::<PyCell>()?;
     m.add_class::<PyBuilder>()?;
     m.add_class::<PySlice>()?;
     m.add_class::<PyDictionary>()?;
+    m.add_class::<PyBoc>()?;
+    m.add_class::<PyAddress>()?;
+    m.add_class::<PyBocInfo>()?;
+    m.add_class::<PyCellDiff>()?;
+    m.add_class::<PyMerkleProof>()?;
+    m.add_class::<PyMerkleUpdate>()?;
     m.add_class::<PySaveList>()?;
     m.add_class::<PyContinuationType>()?;
     m.add_class::<PyContinuation>()?;
     m.add_class::<PyGas>()?;
     m.add_class::<PyVmState>()?;
     m.add_class::<PyVmResult>()?;
+    m.add_class::<PyVm>()?;
+    m.add_class::<PyTraceStep>()?;
+    m.add_class::<PyGasProfile>()?;
+    m.add_class::<PyCoverage>()?;
+    m.add("PlaygroundError", py.get_type::<PlaygroundError>())?;
+    m.add("CellOverflowError", py.get_type::<CellOverflowError>())?;
+    m.add("CellUnderflowError", py.get_type::<CellUnderflowError>())?;
+    m.add("BocError", py.get_type::<BocError>())?;
+    m.add("DictionaryError", py.get_type::<DictionaryError>())?;
+    m.add("InvalidBitstringError", py.get_type::<InvalidBitstringError>())?;
+    m.add("VmExecutionError", py.get_type::<VmExecutionError>())?;
+    m.add("AssemblerError", py.get_type::<AssemblerError>())?;
     m.add_wrapped(wrap_pyfunction!(assemble))?;
+    m.add_wrapped(wrap_pyfunction!(assemble_debug))?;
+    m.add_class::<PyInstruction>()?;
+    m.add_wrapped(wrap_pyfunction!(disassemble))?;
+    m.add_wrapped(wrap_pyfunction!(disassemble_instructions))?;
     m.add_wrapped(wrap_pyfunction!(runvm_generic))?;
     m.add_wrapped(wrap_pyfunction!(ed25519_new_keypair))?;
     m.add_wrapped(wrap_pyfunction!(ed25519_secret_to_public))?;
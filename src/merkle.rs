@@ -0,0 +1,155 @@
+use std::{cell::RefCell, collections::{HashMap, HashSet}};
+use crate::{err, PyCell, runtime_err, utils::{hash_from_int, hash_to_int}};
+use num_bigint::BigUint;
+use pyo3::prelude::*;
+use ton_block::{Deserializable, MerkleProof, MerkleUpdate, Serializable};
+use ton_types::{Cell, CellType, UInt256};
+
+fn collect_cells(root: &Cell) -> PyResult<HashMap<UInt256, Cell>> {
+    let mut cells = HashMap::new();
+    let mut stack = vec!(root.clone());
+    while let Some(cell) = stack.pop() {
+        if cells.insert(cell.repr_hash(), cell.clone()).is_none() {
+            for index in 0..cell.references_count() {
+                stack.push(cell.reference(index).map_err(runtime_err)?)
+            }
+        }
+    }
+    Ok(cells)
+}
+
+// Reads a hash stored in the data of an exotic cell at the byte offset
+fn stored_hash(cell: &Cell, offset: usize) -> UInt256 {
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&cell.data()[offset..offset + 32]);
+    UInt256::from(hash)
+}
+
+// Adapts the python filter of the visited cells to the hash predicate of MerkleProof::create,
+// the root is always visited
+pub(crate) fn make_merkle_proof(py: Python<'_>, root: &Cell, visited: &PyAny) -> PyResult<Cell> {
+    let root_hash = root.repr_hash();
+    let proof = if visited.is_callable() {
+        let cells = collect_cells(root)?;
+        let error = RefCell::new(None);
+        let proof = MerkleProof::create(root, |hash| {
+            if *hash == root_hash || error.borrow().is_some() {
+                return *hash == root_hash
+            }
+            let Some(cell) = cells.get(hash)
+                else { return false };
+            match visited.call1((PyCell::new(cell.clone()).into_py(py),)).and_then(|value| value.is_true()) {
+                Ok(visited) => visited,
+                Err(err) => {
+                    error.replace(Some(err));
+                    false
+                }
+            }
+        });
+        if let Some(err) = error.into_inner() {
+            return Err(err)
+        }
+        proof
+    } else {
+        let mut hashes = HashSet::new();
+        for hash in visited.iter()? {
+            hashes.insert(hash_from_int(&hash?.extract::<BigUint>()?)?);
+        }
+        MerkleProof::create(root, |hash| *hash == root_hash || hashes.contains(hash))
+    };
+    proof.and_then(|proof| proof.serialize()).map_err(runtime_err)
+}
+
+#[derive(Clone)]
+#[pyclass(name = "MerkleProof")]
+pub(crate) struct PyMerkleProof {
+    cell: Cell,
+}
+
+impl PyMerkleProof {
+    pub(crate) fn new(cell: Cell) -> Self {
+        Self { cell }
+    }
+    // hash of the virtual root stored in the proof data
+    fn stored_hash(&self) -> UInt256 {
+        stored_hash(&self.cell, 1)
+    }
+}
+
+#[pymethods]
+impl PyMerkleProof {
+    #[new]
+    fn create(cell: PyCell) -> PyResult<Self> {
+        if cell.cell.cell_type() != CellType::MerkleProof {
+            return err!("cell is not a merkle proof")
+        }
+        Ok(Self::new(cell.cell))
+    }
+    #[staticmethod]
+    fn make(py: Python<'_>, root: PyCell, visited: &PyAny) -> PyResult<Self> {
+        make_merkle_proof(py, &root.cell, visited).map(Self::new)
+    }
+    #[getter]
+    fn cell(&self) -> PyCell {
+        PyCell::new(self.cell.clone())
+    }
+    #[getter]
+    fn root_hash(&self) -> BigUint {
+        hash_to_int(&self.stored_hash())
+    }
+    fn verify(&self, root_hash: BigUint) -> PyResult<bool> {
+        let virtual_root = self.cell.reference(0).map_err(runtime_err)?;
+        let stored_hash = self.stored_hash();
+        Ok(stored_hash == hash_from_int(&root_hash)? && virtual_root.hash(0) == stored_hash)
+    }
+    fn virtualize(&self) -> PyResult<PyCell> {
+        self.cell.reference(0)
+            .map(PyCell::new)
+            .map_err(runtime_err)
+    }
+}
+
+#[derive(Clone)]
+#[pyclass(name = "MerkleUpdate")]
+pub(crate) struct PyMerkleUpdate {
+    cell: Cell,
+}
+
+#[pymethods]
+impl PyMerkleUpdate {
+    #[new]
+    fn create_from_cell(cell: PyCell) -> PyResult<Self> {
+        if cell.cell.cell_type() != CellType::MerkleUpdate {
+            return err!("cell is not a merkle update")
+        }
+        Ok(Self { cell: cell.cell })
+    }
+    #[staticmethod]
+    fn create(old: PyCell, new: PyCell) -> PyResult<Self> {
+        let update = MerkleUpdate::create(&old.cell, &new.cell).map_err(runtime_err)?;
+        let cell = update.serialize().map_err(runtime_err)?;
+        Ok(Self { cell })
+    }
+    #[getter]
+    fn cell(&self) -> PyCell {
+        PyCell::new(self.cell.clone())
+    }
+    #[getter]
+    fn old_hash(&self) -> BigUint {
+        hash_to_int(&stored_hash(&self.cell, 1))
+    }
+    #[getter]
+    fn new_hash(&self) -> BigUint {
+        hash_to_int(&stored_hash(&self.cell, 33))
+    }
+    fn apply(&self, old: PyCell) -> PyResult<PyCell> {
+        if old.cell.repr_hash() != stored_hash(&self.cell, 1) {
+            return err!("merkle update is not applicable to the cell")
+        }
+        // the hash of the new tree is checked against the stored one as well
+        let update = MerkleUpdate::construct_from_cell(self.cell.clone()).map_err(runtime_err)?;
+        update.apply_for(&old.cell)
+            .map(PyCell::new)
+            .map_err(runtime_err)
+    }
+}
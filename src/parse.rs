@@ -0,0 +1,173 @@
+use crate::{err, exotic::exotic_cell, utils::{runtime_err, CellOverflowError, InvalidBitstringError}};
+use pyo3::prelude::*;
+use ton_types::{Cell, SliceData};
+
+fn cell_with_refs(bitstring: &str, refs: Vec<Cell>, exotic: bool) -> PyResult<Cell> {
+    let slice = SliceData::from_string(bitstring)
+        .map_err(|_| InvalidBitstringError::new_err(format!("invalid bitstring \"{}\"", bitstring)))?;
+    let mut builder = slice.as_builder();
+    if refs.len() > 4 {
+        return Err(CellOverflowError::new_err("cell can't contain more than 4 references"))
+    }
+    for child in refs {
+        builder.checked_append_reference(child).map_err(runtime_err)?;
+    }
+    if exotic {
+        return exotic_cell(builder)
+    }
+    builder.into_cell().map_err(runtime_err)
+}
+
+// Converts a binary bitstring into the hex form with the completion tag
+fn binary_to_hex(binary: &str) -> PyResult<String> {
+    if !binary.chars().all(|c| c == '0' || c == '1') {
+        return Err(InvalidBitstringError::new_err(format!("invalid binary bitstring \"{}\"", binary)))
+    }
+    let mut bits = binary.to_string();
+    let tagged = bits.len() % 4 != 0;
+    if tagged {
+        bits.push('1');
+        while bits.len() % 4 != 0 {
+            bits.push('0');
+        }
+    }
+    let mut hex = (0..bits.len()).step_by(4)
+        .map(|i| format!("{:x}", u8::from_str_radix(&bits[i..i + 4], 2).unwrap()))
+        .collect::<String>();
+    if tagged {
+        hex.push('_');
+    }
+    Ok(hex)
+}
+
+// Parser of the C("hex", C(...), ...) format produced by dump_cell_generic
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_spaces(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+    fn peek(&mut self) -> Option<char> {
+        self.skip_spaces();
+        self.text[self.pos..].chars().next()
+    }
+    fn expect(&mut self, expected: char) -> PyResult<()> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => err!("expected '{}' at position {}, found '{}'", expected, self.pos, c),
+            None => err!("expected '{}' at position {}, found end of text", expected, self.pos),
+        }
+    }
+    fn parse_cell(&mut self) -> PyResult<Cell> {
+        self.skip_spaces();
+        let rest = &self.text[self.pos..];
+        let name_len = rest.find(|c: char| !c.is_alphanumeric() && c != '_' && c != '.').unwrap_or(rest.len());
+        if name_len == 0 {
+            return err!("expected cell constructor at position {}", self.pos)
+        }
+        self.pos += name_len;
+        self.expect('(')?;
+        let quote = match self.peek() {
+            Some(c) if c == '"' || c == '\'' => c,
+            _ => return err!("expected bitstring literal at position {}", self.pos),
+        };
+        self.pos += 1;
+        let end = match self.text[self.pos..].find(quote) {
+            Some(end) => self.pos + end,
+            None => return err!("unterminated bitstring literal at position {}", self.pos),
+        };
+        let bitstring = &self.text[self.pos..end];
+        self.pos = end + 1;
+        let mut refs = Vec::new();
+        while self.peek() == Some(',') {
+            self.pos += 1;
+            if self.peek() == Some(')') {
+                break
+            }
+            refs.push(self.parse_cell()?);
+        }
+        self.expect(')')?;
+        cell_with_refs(bitstring, refs, false)
+    }
+}
+
+fn parse_playground(text: &str) -> PyResult<Cell> {
+    let mut parser = Parser { text, pos: 0 };
+    let cell = parser.parse_cell()?;
+    if let Some(c) = parser.peek() {
+        return err!("unexpected '{}' at position {}", c, parser.pos)
+    }
+    Ok(cell)
+}
+
+// Parser of the Fift x{ABCD_} dump format with the references indented below their parents
+fn parse_fift(text: &str) -> PyResult<Cell> {
+    // depth, bitstring, exotic flag and children of the cells on the path from the root to the current line
+    let mut stack: Vec<(usize, String, bool, Vec<Cell>)> = Vec::new();
+    let mut root = None;
+    for (number, line) in text.lines().enumerate() {
+        let content = line.trim();
+        if content.is_empty() {
+            continue
+        }
+        let (exotic, content) = match content.strip_prefix("SPECIAL ") {
+            Some(content) => (true, content),
+            None => (false, content),
+        };
+        let depth = line.len() - line.trim_start().len();
+        let bitstring = if let Some(hex) = content.strip_prefix("x{").and_then(|c| c.strip_suffix('}')) {
+            hex.to_string()
+        } else if let Some(binary) = content.strip_prefix("b{").and_then(|c| c.strip_suffix('}')) {
+            binary_to_hex(binary)?
+        } else {
+            return err!("line {}: expected x{{...}} or b{{...}}, found \"{}\"", number + 1, content)
+        };
+        while let Some((top, _, _, _)) = stack.last() {
+            if *top < depth {
+                break
+            }
+            let (_, bitstring, exotic, refs) = stack.pop().unwrap();
+            let cell = cell_with_refs(&bitstring, refs, exotic)?;
+            match stack.last_mut() {
+                Some((_, _, _, parent)) => parent.push(cell),
+                None => root = Some(cell),
+            }
+        }
+        if root.is_some() {
+            return err!("line {}: text contains more than one root cell", number + 1)
+        }
+        if let Some((top, _, _, _)) = stack.last() {
+            if depth != top + 1 {
+                return err!("line {}: unexpected indentation", number + 1)
+            }
+        }
+        stack.push((depth, bitstring, exotic, Vec::new()));
+    }
+    while let Some((_, bitstring, exotic, refs)) = stack.pop() {
+        let cell = cell_with_refs(&bitstring, refs, exotic)?;
+        match stack.last_mut() {
+            Some((_, _, _, parent)) => parent.push(cell),
+            None => root = Some(cell),
+        }
+    }
+    match root {
+        Some(cell) => Ok(cell),
+        None => err!("text contains no cells"),
+    }
+}
+
+pub(crate) fn parse_cell(text: &str) -> PyResult<Cell> {
+    let trimmed = text.trim_start();
+    if trimmed.starts_with("x{") || trimmed.starts_with("b{") {
+        parse_fift(text)
+    } else {
+        parse_playground(text)
+    }
+}
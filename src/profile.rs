@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use crate::{trace::TraceRecord, utils::{hash_from_int, hash_to_int}};
+use num_bigint::BigUint;
+use pyo3::prelude::*;
+use ton_types::UInt256;
+
+#[derive(Clone, Copy, Default)]
+struct Counter {
+    gas: i64,
+    steps: u64,
+}
+
+impl Counter {
+    fn add(&mut self, gas: i64) {
+        self.gas += gas;
+        self.steps += 1;
+    }
+}
+
+#[derive(Clone, Default)]
+#[pyclass(name = "GasProfile")]
+pub(crate) struct PyGasProfile {
+    total: Counter,
+    by_mnemonic: HashMap<String, Counter>,
+    by_cell: HashMap<UInt256, Counter>,
+    by_position: HashMap<(UInt256, usize), Counter>,
+    // (code cell, mnemonic) pairs used as two-frame stacks of the folded export
+    by_cell_mnemonic: HashMap<(UInt256, String), Counter>,
+}
+
+impl PyGasProfile {
+    pub(crate) fn add(&mut self, record: &TraceRecord) {
+        let mnemonic = record.mnemonic().to_string();
+        self.total.add(record.gas_cmd);
+        self.by_mnemonic.entry(mnemonic.clone()).or_default().add(record.gas_cmd);
+        self.by_cell.entry(record.cell_hash.clone()).or_default().add(record.gas_cmd);
+        self.by_position.entry((record.cell_hash.clone(), record.offset)).or_default().add(record.gas_cmd);
+        self.by_cell_mnemonic.entry((record.cell_hash.clone(), mnemonic)).or_default().add(record.gas_cmd);
+    }
+}
+
+#[pymethods]
+impl PyGasProfile {
+    #[getter]
+    fn total_gas(&self) -> i64 {
+        self.total.gas
+    }
+    #[getter]
+    fn total_steps(&self) -> u64 {
+        self.total.steps
+    }
+    fn by_mnemonic(&self) -> HashMap<String, (i64, u64)> {
+        self.by_mnemonic.iter()
+            .map(|(mnemonic, counter)| (mnemonic.clone(), (counter.gas, counter.steps)))
+            .collect()
+    }
+    fn by_cell(&self) -> HashMap<BigUint, (i64, u64)> {
+        self.by_cell.iter()
+            .map(|(hash, counter)| (hash_to_int(hash), (counter.gas, counter.steps)))
+            .collect()
+    }
+    fn by_line(&self, debug_map: HashMap<(BigUint, usize), (String, usize)>) -> PyResult<HashMap<(String, usize), (i64, u64)>> {
+        let mut lines = HashMap::<(String, usize), Counter>::new();
+        for ((cell_hash, offset), pos) in debug_map {
+            let Some(counter) = self.by_position.get(&(hash_from_int(&cell_hash)?, offset))
+                else { continue };
+            let entry = lines.entry(pos).or_default();
+            entry.gas += counter.gas;
+            entry.steps += counter.steps;
+        }
+        Ok(lines.into_iter()
+            .map(|(pos, counter)| (pos, (counter.gas, counter.steps)))
+            .collect())
+    }
+    fn folded(&self) -> String {
+        let mut lines = self.by_cell_mnemonic.iter()
+            .map(|((hash, mnemonic), counter)| format!("{};{} {}", hash.to_hex_string(), mnemonic, counter.gas))
+            .collect::<Vec<_>>();
+        lines.sort();
+        lines.join("\n")
+    }
+    fn __str__(&self) -> String {
+        let mut rows = self.by_mnemonic.iter().collect::<Vec<_>>();
+        rows.sort_by(|(m1, c1), (m2, c2)| c2.gas.cmp(&c1.gas).then(m1.cmp(m2)));
+        let mut output = format!("{:<16} {:>12} {:>10}\n", "INSN", "GAS", "STEPS");
+        for (mnemonic, counter) in rows {
+            output += &format!("{:<16} {:>12} {:>10}\n", mnemonic, counter.gas, counter.steps);
+        }
+        output += &format!("{:<16} {:>12} {:>10}", "TOTAL", self.total.gas, self.total.steps);
+        output
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{err, utils::{runtime_err, CellOverflowError}};
+use pyo3::prelude::*;
+use ton_types::{BuilderData, Cell, SliceData};
+
+const MAX_CELL_BITS: usize = 1023;
+const MAX_CELL_BYTES: usize = MAX_CELL_BITS / 8;
+
+// Tells whether the cell ending at `end` bits has no room left for another byte, which is the case
+// for every cell of a chain but the last one
+fn is_full(end: usize) -> bool {
+    end + 8 > MAX_CELL_BITS
+}
+
+// Stores as many bytes as fit into the builder, and the rest into a chain of cells
+// each linked to the next one by its only reference
+pub(crate) fn store_bytes_chain(builder: &mut BuilderData, data: &[u8]) -> PyResult<()> {
+    let head = data.len().min((MAX_CELL_BITS - builder.length_in_bits()) / 8);
+    let tail = &data[head..];
+    let refs = builder.references_used();
+    if !tail.is_empty() && refs != 0 {
+        return Err(CellOverflowError::new_err("the chain can't be continued from a builder with references"))
+    }
+    // a full cell with a single reference is read as a chain link
+    if tail.is_empty() && refs == 1 && is_full(builder.length_in_bits() + head * 8) {
+        return Err(CellOverflowError::new_err("the data fills the builder, so its reference would be read as a chain link"))
+    }
+    let mut next = None;
+    for chunk in tail.chunks(MAX_CELL_BYTES).rev() {
+        let mut cell = BuilderData::with_raw(chunk.to_vec(), chunk.len() * 8).map_err(runtime_err)?;
+        if let Some(child) = next.take() {
+            cell.checked_append_reference(child).map_err(runtime_err)?;
+        }
+        next = Some(cell.into_cell().map_err(runtime_err)?);
+    }
+    builder.append_raw(&data[..head], head * 8).map_err(runtime_err)?;
+    if let Some(child) = next {
+        builder.checked_append_reference(child).map_err(runtime_err)?;
+    }
+    Ok(())
+}
+
+fn load_bytes(slice: &SliceData) -> PyResult<Vec<u8>> {
+    let bits = slice.remaining_bits();
+    if bits % 8 != 0 {
+        return err!("chain cell contains {} bits, which is not a whole number of bytes", bits)
+    }
+    slice.clone().get_next_bits(bits).map_err(runtime_err)
+}
+
+// The next cell of the chain, linked by the only reference of a full cell
+fn chain_link(slice: &SliceData) -> PyResult<Option<Cell>> {
+    if slice.remaining_references() != 1 || !is_full(slice.pos() + slice.remaining_bits()) {
+        return Ok(None)
+    }
+    slice.reference(0).map(Some).map_err(runtime_err)
+}
+
+// Reads the remaining bytes of the slice followed by the bytes of the chain linked to it,
+// and tells whether the slice reference was followed as a chain link
+pub(crate) fn load_bytes_chain(slice: &SliceData) -> PyResult<(Vec<u8>, bool)> {
+    let mut data = load_bytes(slice)?;
+    let mut next = chain_link(slice)?;
+    let linked = next.is_some();
+    while let Some(cell) = next {
+        let slice = SliceData::load_cell(cell).map_err(runtime_err)?;
+        data.extend(load_bytes(&slice)?);
+        next = chain_link(&slice)?;
+    }
+    Ok((data, linked))
+}
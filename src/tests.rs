@@ -1,6 +1,6 @@
 #![cfg(test)]
 
-use crate::dump_cell;
+use crate::{dump_cell, disasm::{Node, parse_nodes, render_nodes}};
 use ton_types::{Cell, SliceData};
 
 fn __(data: &str, refs: Vec<Cell>) -> ton_types::Result<Cell> {
@@ -49,3 +49,19 @@ fn test_dump_cell() -> ton_types::Status {
 
     Ok(())
 }
+
+#[test]
+fn test_parse_disasm_nodes() {
+    let text = "PUSHINT 1\nPUSHCONT {\n  INC\n  PUSHREFCONT {\n  }\n}\nIFELSE {\n  DEC\n}\n{\n  NOP\n}\n";
+    let nodes = parse_nodes(&mut text.lines().peekable());
+    let leaf = |text: &str| Node { text: text.to_string(), blocks: vec!() };
+    assert_eq!(nodes, vec!(
+        leaf("PUSHINT 1"),
+        Node { text: "PUSHCONT".to_string(), blocks: vec!(vec!(
+            leaf("INC"),
+            Node { text: "PUSHREFCONT".to_string(), blocks: vec!(vec!()) },
+        )) },
+        Node { text: "IFELSE".to_string(), blocks: vec!(vec!(leaf("DEC")), vec!(leaf("NOP"))) },
+    ));
+    assert_eq!(render_nodes(&nodes, 0), "PUSHINT 1\nPUSHCONT {\n  INC\n  PUSHREFCONT {\n\n  }\n}\nIFELSE {\n  DEC\n} {\n  NOP\n}");
+}
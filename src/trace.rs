@@ -0,0 +1,138 @@
+use crate::utils::{convert_from_vm, hash_from_int, hash_to_int};
+use num_bigint::BigUint;
+use pyo3::{
+    prelude::*,
+    exceptions::PyValueError,
+};
+use ton_types::{SliceData, UInt256};
+use ton_vm::{
+    executor::{EngineTraceInfo, EngineTraceInfoType},
+    stack::StackItem,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum TraceMode {
+    Off,
+    Print,
+    Collect,
+}
+
+impl<'a> FromPyObject<'a> for TraceMode {
+    fn extract(value: &'a PyAny) -> PyResult<Self> {
+        if let Ok(enabled) = value.extract::<bool>() {
+            return Ok(if enabled { TraceMode::Print } else { TraceMode::Off })
+        }
+        match value.extract::<&str>()? {
+            "print" => Ok(TraceMode::Print),
+            "collect" => Ok(TraceMode::Collect),
+            mode => Err(PyValueError::new_err(format!("unknown trace mode \"{}\"", mode))),
+        }
+    }
+}
+
+pub(crate) enum Breakpoint {
+    // code cell representation hash and bit offset of the instruction
+    Position(UInt256, usize),
+    Mnemonic(String),
+}
+
+impl<'a> FromPyObject<'a> for Breakpoint {
+    fn extract(value: &'a PyAny) -> PyResult<Self> {
+        if let Ok(mnemonic) = value.extract::<&str>() {
+            return Ok(Breakpoint::Mnemonic(mnemonic.to_uppercase()))
+        }
+        let (hash, offset) = value.extract::<(BigUint, usize)>()?;
+        Ok(Breakpoint::Position(hash_from_int(&hash)?, offset))
+    }
+}
+
+impl Breakpoint {
+    pub(crate) fn is_at(&self, code: &SliceData) -> bool {
+        match self {
+            Breakpoint::Position(hash, offset) =>
+                code.pos() == *offset && code.cell().repr_hash() == *hash,
+            Breakpoint::Mnemonic(_) => false,
+        }
+    }
+    pub(crate) fn is_on(&self, record: &TraceRecord) -> bool {
+        match self {
+            Breakpoint::Position(_, _) => false,
+            Breakpoint::Mnemonic(mnemonic) => record.mnemonic() == mnemonic,
+        }
+    }
+}
+
+// Trace step captured inside the engine callback, converted to python objects afterwards
+pub(crate) struct TraceRecord {
+    pub(crate) step: u32,
+    pub(crate) insn: String,
+    // code cell representation hash and bit offset of the instruction
+    pub(crate) cell_hash: UInt256,
+    pub(crate) offset: usize,
+    pub(crate) gas_cmd: i64,
+    pub(crate) gas_used: i64,
+    pub(crate) exception: bool,
+    pub(crate) stack: Vec<StackItem>,
+}
+
+impl TraceRecord {
+    // the stack is copied only when it is going to be converted into a TraceStep
+    pub(crate) fn from_info(info: &EngineTraceInfo, with_stack: bool) -> Option<Self> {
+        match info.info_type {
+            EngineTraceInfoType::Start | EngineTraceInfoType::Dump => None,
+            _ => Some(Self {
+                step: info.step,
+                insn: info.cmd_str.clone(),
+                cell_hash: info.cmd_code.cell().repr_hash(),
+                offset: info.cmd_code.pos(),
+                gas_cmd: info.gas_cmd,
+                gas_used: info.gas_used,
+                exception: info.info_type == EngineTraceInfoType::Exception,
+                stack: if with_stack { info.stack.iter().cloned().collect() } else { Vec::new() },
+            })
+        }
+    }
+    pub(crate) fn mnemonic(&self) -> &str {
+        self.insn.split_whitespace().next().unwrap_or_default()
+    }
+}
+
+#[derive(Clone)]
+#[pyclass(get_all, name = "TraceStep")]
+pub(crate) struct PyTraceStep {
+    step: u32,
+    insn: String,
+    cell_hash: BigUint,
+    offset: usize,
+    gas_cmd: i64,
+    gas_used: i64,
+    exception: bool,
+    stack: Vec<PyObject>,
+}
+
+impl PyTraceStep {
+    pub(crate) fn new(py: Python<'_>, record: &TraceRecord) -> PyResult<Self> {
+        let mut stack = Vec::new();
+        for item in record.stack.iter() {
+            stack.push(convert_from_vm(py, item)?)
+        }
+        Ok(Self {
+            step: record.step,
+            insn: record.insn.clone(),
+            cell_hash: hash_to_int(&record.cell_hash),
+            offset: record.offset,
+            gas_cmd: record.gas_cmd,
+            gas_used: record.gas_used,
+            exception: record.exception,
+            stack,
+        })
+    }
+}
+
+#[pymethods]
+impl PyTraceStep {
+    fn __str__(&self) -> String {
+        let prefix = if self.exception { "EXCEPTION " } else { "" };
+        format!("{}STEP {} {} (gas {}/{})", prefix, self.step, self.insn, self.gas_cmd, self.gas_used)
+    }
+}
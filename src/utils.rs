@@ -5,30 +5,57 @@ use crate::{
 };
 use num_bigint::{BigInt, BigUint, Sign};
 use pyo3::{
+    create_exception,
     exceptions::PyRuntimeError,
     prelude::{IntoPy, Python, PyAny, PyObject, PyResult},
     PyErr,
     types::{PyList, PyLong},
 };
-use ton_types::Cell;
-use ton_vm::stack::{
-    StackItem,
-    integer::{IntegerData, utils::process_value},
+use ton_types::{Cell, CellType, ExceptionCode, UInt256};
+use ton_vm::{
+    error::tvm_exception_code,
+    stack::{
+        StackItem,
+        integer::{IntegerData, utils::process_value},
+    },
 };
 
+create_exception!(ever_playground, PlaygroundError, PyRuntimeError, "Base class of ever_playground errors.");
+create_exception!(ever_playground, CellOverflowError, PlaygroundError, "Cell data or references overflow.");
+create_exception!(ever_playground, CellUnderflowError, PlaygroundError, "Not enough data or references in a cell.");
+create_exception!(ever_playground, BocError, PlaygroundError, "Bag of cells serialization error.");
+create_exception!(ever_playground, DictionaryError, PlaygroundError, "Dictionary error.");
+create_exception!(ever_playground, InvalidBitstringError, PlaygroundError, "Malformed bitstring.");
+create_exception!(ever_playground, VmExecutionError, PlaygroundError, "TVM failed to execute the code.");
+create_exception!(ever_playground, AssemblerError, PlaygroundError, "TVM assembly source failed to compile.");
+
 macro_rules! err {
     ($error:literal) => {
-        PyResult::Err(PyRuntimeError::new_err($error))
+        PyResult::Err($crate::utils::PlaygroundError::new_err($error))
     };
     ($fmt:expr, $($arg:tt)+) => {
-        PyResult::Err(PyRuntimeError::new_err(format!($fmt, $($arg)+)))
+        PyResult::Err($crate::utils::PlaygroundError::new_err(format!($fmt, $($arg)+)))
     };
 }
 
 pub(crate) use err;
 
 pub(crate) fn runtime_err(err: ton_types::Error) -> PyErr {
-    PyRuntimeError::new_err(err.to_string())
+    let message = err.to_string();
+    match tvm_exception_code(&err) {
+        Some(ExceptionCode::CellOverflow) => CellOverflowError::new_err(message),
+        Some(ExceptionCode::CellUnderflow) => CellUnderflowError::new_err(message),
+        Some(ExceptionCode::DictionaryError) => DictionaryError::new_err(message),
+        _ => PlaygroundError::new_err(message),
+    }
+}
+
+pub(crate) fn boc_err(err: ton_types::Error) -> PyErr {
+    BocError::new_err(err.to_string())
+}
+
+pub(crate) fn dict_err(err: ton_types::Error) -> PyErr {
+    DictionaryError::new_err(err.to_string())
 }
 
 pub(crate) fn signed_int_serialize(mut integer: BigInt, bits: usize) -> PyResult<Vec<u8>> {
@@ -42,7 +69,7 @@ pub(crate) fn signed_int_serialize(mut integer: BigInt, bits: usize) -> PyResult
 
 pub(crate) fn unsigned_int_serialize(integer: BigInt, bits: usize) -> PyResult<Vec<u8>> {
     let mut integer = integer.to_biguint()
-        .ok_or(PyRuntimeError::new_err("integer is negative"))?;
+        .ok_or(PlaygroundError::new_err("integer is negative"))?;
     let excess_bits = excess_bits(bits);
     if excess_bits != 0 {
         integer <<= 8 - excess_bits;
@@ -69,6 +96,20 @@ pub(crate) fn unsigned_int_deserialize(bytes: &[u8], bits: usize) -> PyResult<Bi
     Ok(integer)
 }
 
+pub(crate) fn hash_from_int(hash: &BigUint) -> PyResult<UInt256> {
+    let bytes = hash.to_bytes_be();
+    if bytes.len() > 32 {
+        return err!("cell hash must fit into 256 bits")
+    }
+    let mut buffer = [0; 32];
+    buffer[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(UInt256::from(buffer))
+}
+
+pub(crate) fn hash_to_int(hash: &UInt256) -> BigUint {
+    BigUint::from_bytes_be(hash.as_slice())
+}
+
 fn excess_bits(bits: usize) -> usize {
     bits & 0b111
 }
@@ -138,6 +179,47 @@ pub(crate) fn dump_cell(cell: Cell) -> String {
     dump_cell_generic(cell, "C", "    ")
 }
 
+// Dumps the cell tree line by line with one space of indentation per level, as Fift does
+fn dump_cell_lines(cell: Cell, line: impl Fn(&Cell) -> String) -> String {
+    let mut output = String::new();
+    let mut stack = vec!((cell, 0));
+    while let Some((cell, indent)) = stack.pop() {
+        output += &format!("{}{}\n", " ".repeat(indent), line(&cell));
+        for i in (0..cell.references_count()).rev() {
+            stack.push((cell.reference(i).unwrap(), indent + 1));
+        }
+    }
+    output
+}
+
+fn fift_bitstring(cell: &Cell) -> String {
+    format!("x{{{}}}", cell.to_hex_string(true).to_uppercase())
+}
+
+// Same as the output of Fift's csr. word
+pub(crate) fn dump_cell_fift(cell: Cell) -> String {
+    dump_cell_lines(cell, |cell| {
+        let special = if cell.cell_type() != CellType::Ordinary { "SPECIAL " } else { "" };
+        format!("{}{}", special, fift_bitstring(cell))
+    })
+}
+
+pub(crate) fn dump_cell_tree(cell: Cell) -> String {
+    dump_cell_lines(cell, |cell| {
+        let mut line = format!("{} bits={} refs={} depth={} hash={}",
+            fift_bitstring(cell),
+            cell.bit_length(),
+            cell.references_count(),
+            cell.repr_depth(),
+            cell.repr_hash().to_hex_string(),
+        );
+        if cell.cell_type() != CellType::Ordinary {
+            line += &format!(" type={}", crate::exotic::cell_type_name(cell.cell_type()));
+        }
+        line
+    })
+}
+
 pub(crate) fn convert_to_vm(value: &PyAny) -> PyResult<StackItem> {
     if value.is_none() {
         Ok(StackItem::None)
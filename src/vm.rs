@@ -1,11 +1,16 @@
-use crate::{PyContinuation, PySaveList, convert_from_vm, runtime_err, PyDictionary};
-use pyo3::{
-    prelude::*,
-    exceptions::PyRuntimeError,
+use std::sync::{Arc, Mutex};
+use crate::{
+    err, PyContinuation, PySaveList, convert_from_vm, runtime_err, PyDictionary, VmExecutionError,
+    coverage::PyCoverage,
+    profile::PyGasProfile,
+    trace::{Breakpoint, PyTraceStep, TraceMode, TraceRecord},
 };
+use pyo3::prelude::*;
+use ton_types::Cell;
 use ton_vm::{
     error::tvm_exception_full,
     executor::{Engine, EngineTraceInfo, gas::gas_state::Gas},
+    stack::{continuation::ContinuationData, savelist::SaveList},
 };
 
 #[derive(Clone)]
@@ -87,61 +92,349 @@ pub(crate) struct PyVmResult {
     state: PyVmState,
     exit_code: i32,
     exception_value: Option<PyObject>,
+    trace: Option<Vec<PyTraceStep>>,
+    interrupted: bool,
+    breakpoint: Option<usize>,
+    profile: Option<PyGasProfile>,
 }
 
-#[pyfunction]
-#[pyo3(signature = (state, capabilities = 0, trace = false, libs = Vec::new()))]
-pub(crate) fn runvm_generic(py: Python<'_>, state: PyVmState, capabilities: u64, trace: bool, libs: Vec<PyDictionary>) -> PyResult<PyObject> {
+fn create_engine(py: Python<'_>, state: PyVmState, capabilities: u64, libs: Vec<PyDictionary>) -> PyResult<Engine> {
     let cc = state.cc.cont(py)?;
-    let mut engine = Engine::with_capabilities(capabilities).setup_with_libraries(
+    Ok(Engine::with_capabilities(capabilities).setup_with_libraries(
         cc.code().clone(),
         Some(state.regs.savelist),
         Some(cc.stack),
         Some(state.gas.gas),
         libs.iter().map(|dict| dict.map.clone()).collect::<Vec<_>>(),
-    );
-    if trace {
-        engine.set_trace_callback(trace_callback);
-    }
-    let exit_code;
-    let mut exception_value = None;
-    match engine.execute() {
-        Ok(code) => exit_code = code,
+    ))
+}
+
+// Splits an engine outcome into an exit code and an optional exception value
+fn process_outcome(py: Python<'_>, outcome: ton_types::Result<i32>) -> PyResult<(i32, Option<PyObject>)> {
+    match outcome {
+        Ok(code) => Ok((code, None)),
         Err(err) => if let Some(exception) = tvm_exception_full(&err) {
-            exit_code = exception.exception_or_custom_code();
-            exception_value = Some(convert_from_vm(py, &exception.value)?);
+            let value = convert_from_vm(py, &exception.value)?;
+            Ok((exception.exception_or_custom_code(), Some(value)))
         } else {
-            return Err(PyRuntimeError::new_err(format!("execution failed: {}", err)))
+            Err(VmExecutionError::new_err(format!("execution failed: {}", err)))
         }
     }
+}
+
+// Engine-side copy of the state, converted to Python objects only when needed
+struct EngineSnapshot {
+    cc: ContinuationData,
+    ctrls: SaveList,
+    steps: u32,
+    gas: Gas,
+    committed: Option<(Cell, Cell)>,
+}
+
+impl EngineSnapshot {
+    fn take(engine: &Engine) -> PyResult<Self> {
+        let committed_state = engine.get_committed_state();
+        let committed = if committed_state.is_committed() {
+            let c4 = committed_state.get_root();
+            let c5 = committed_state.get_actions();
+            let c4 = c4.as_cell().map_err(runtime_err)?.clone();
+            let c5 = c5.as_cell().map_err(runtime_err)?.clone();
+            Some((c4, c5))
+        } else {
+            None
+        };
+        Ok(Self {
+            cc: engine.cc().clone(),
+            ctrls: engine.ctrls().clone(),
+            steps: engine.steps(),
+            gas: engine.get_gas().clone(),
+            committed,
+        })
+    }
+    fn into_state(self, py: Python<'_>) -> PyResult<PyVmState> {
+        let (committed_c4, committed_c5) = match self.committed {
+            Some((c4, c5)) => (Some(crate::PyCell::new(c4)), Some(crate::PyCell::new(c5))),
+            None => (None, None),
+        };
 
-    let committed_state = engine.get_committed_state();
-    let (committed_c4, committed_c5) = if committed_state.is_committed() {
-        let c4 = committed_state.get_root();
-        let c5 = committed_state.get_actions();
-        let c4 = c4.as_cell().map_err(runtime_err)?.clone();
-        let c5 = c5.as_cell().map_err(runtime_err)?.clone();
-        (Some(crate::PyCell::new(c4)), Some(crate::PyCell::new(c5)))
-    } else {
-        (None, None)
+        Ok(PyVmState::new(
+            PyContinuation::new(py, &self.cc)?,
+            PySaveList::new(self.ctrls),
+            self.steps,
+            PyGas::new(self.gas),
+            committed_c4,
+            committed_c5,
+        ))
+    }
+}
+
+fn engine_state(py: Python<'_>, engine: &Engine) -> PyResult<PyVmState> {
+    EngineSnapshot::take(engine)?.into_state(py)
+}
+
+// Gas profile and coverage gathered while the engine runs, so that records need not be kept
+#[derive(Default)]
+struct Aggregates {
+    profile: Option<PyGasProfile>,
+    coverage: Option<PyCoverage>,
+}
+
+impl Aggregates {
+    fn add(&mut self, record: &TraceRecord) {
+        if let Some(profile) = self.profile.as_mut() {
+            profile.add(record)
+        }
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.add(record)
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (state, capabilities = 0, trace = TraceMode::Off, libs = Vec::new(), hook = None, breakpoints = Vec::new(), profile = false, coverage = None))]
+#[allow(clippy::too_many_arguments)] // mirrors the keyword arguments of the python function
+pub(crate) fn runvm_generic(
+    py: Python<'_>,
+    state: PyVmState,
+    capabilities: u64,
+    trace: TraceMode,
+    libs: Vec<PyDictionary>,
+    hook: Option<PyObject>,
+    breakpoints: Vec<Breakpoint>,
+    profile: bool,
+    coverage: Option<Py<PyCoverage>>,
+) -> PyResult<PyObject> {
+    let mut engine = create_engine(py, state, capabilities, libs)?;
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let aggregates = Arc::new(Mutex::new(Aggregates {
+        profile: profile.then(PyGasProfile::default),
+        coverage: coverage.is_some().then(PyCoverage::default),
+    }));
+    let stepping = hook.is_some() || !breakpoints.is_empty();
+    // records are kept only to be converted into trace steps or to be checked against breakpoints,
+    // otherwise they are aggregated right away
+    let keep = trace == TraceMode::Collect || stepping;
+    let with_stack = trace == TraceMode::Collect || hook.is_some();
+    let aggregate = profile || coverage.is_some();
+    if trace == TraceMode::Print || keep || aggregate {
+        let records = records.clone();
+        let aggregates = aggregates.clone();
+        let print = trace == TraceMode::Print;
+        engine.set_trace_callback(move |engine, info| {
+            if print {
+                trace_callback(engine, info)
+            }
+            if !keep && !aggregate {
+                return
+            }
+            if let Some(record) = TraceRecord::from_info(info, with_stack) {
+                if keep {
+                    records.lock().unwrap().push(record)
+                } else {
+                    aggregates.lock().unwrap().add(&record)
+                }
+            }
+        })
+    }
+    let by_mnemonic = breakpoints.iter().any(|bp| matches!(bp, Breakpoint::Mnemonic(_)));
+
+    // when stepping, the engine is driven manually so that it can be stopped between instructions;
+    // breakpoints are not checked on the first instruction, so a paused state can be resumed
+    // with the same breakpoints
+    let mut trace_steps = Vec::new();
+    let mut interrupted = false;
+    let mut breakpoint = None;
+    let mut paused_state = None;
+    let mut first = true;
+    let outcome = 'run: loop {
+        if !first {
+            if let Some(index) = breakpoints.iter().position(|bp| bp.is_at(engine.cc().code())) {
+                breakpoint = Some(index);
+                break 'run Ok(0)
+            }
+        }
+        // mnemonics are known only after the instruction is executed, so the state before it is kept
+        let before = if by_mnemonic && !first { Some(EngineSnapshot::take(&engine)?) } else { None };
+        first = false;
+        let outcome = if stepping {
+            engine.step()
+        } else {
+            engine.execute().map(Some)
+        };
+        let pending = std::mem::take(&mut *records.lock().unwrap());
+        if let Some(state) = before {
+            let hit = pending.iter().find_map(|record|
+                breakpoints.iter().position(|bp| bp.is_on(record)));
+            if let Some(index) = hit {
+                breakpoint = Some(index);
+                paused_state = Some(state.into_state(py)?);
+                break 'run Ok(0)
+            }
+        }
+        for record in pending.iter() {
+            aggregates.lock().unwrap().add(record);
+            if trace != TraceMode::Collect && hook.is_none() {
+                continue
+            }
+            let step = PyTraceStep::new(py, record)?;
+            if trace == TraceMode::Collect {
+                trace_steps.push(step.clone());
+            }
+            if let Some(hook) = &hook {
+                let value = hook.call1(py, (step,))?;
+                if !value.is_none(py) {
+                    interrupted = true;
+                    break 'run Ok(value.extract::<i32>(py)?)
+                }
+            }
+        }
+        match outcome {
+            Ok(None) => continue,
+            Ok(Some(code)) => break Ok(code),
+            Err(err) => break Err(err),
+        }
     };
 
-    let result_state = PyVmState::new(
-        PyContinuation::new(py, engine.cc())?,
-        PySaveList::new(engine.ctrls().clone()),
-        engine.steps(),
-        PyGas::new(engine.get_gas().clone()),
-        committed_c4,
-        committed_c5,
-    );
+    let (exit_code, exception_value) = process_outcome(py, outcome)?;
+    let aggregates = std::mem::take(&mut *aggregates.lock().unwrap());
+    if let (Some(coverage), Some(hits)) = (&coverage, aggregates.coverage) {
+        coverage.borrow_mut(py).extend(hits)
+    }
+    let state = match paused_state {
+        Some(state) => state,
+        None => engine_state(py, &engine)?,
+    };
     let result = PyVmResult {
-        state: result_state,
+        state,
         exit_code,
         exception_value,
+        trace: (trace == TraceMode::Collect).then_some(trace_steps),
+        interrupted: interrupted || breakpoint.is_some(),
+        breakpoint,
+        profile: aggregates.profile,
     };
     Ok(result.into_py(py))
 }
 
+#[pyclass(unsendable, name = "Vm")]
+pub(crate) struct PyVm {
+    engine: Engine,
+    exit_code: Option<i32>,
+    exception_value: Option<PyObject>,
+}
+
+impl PyVm {
+    fn step_once(&mut self, py: Python<'_>) -> PyResult<Option<i32>> {
+        if self.exit_code.is_some() {
+            return err!("execution is already finished")
+        }
+        let outcome = match self.engine.step() {
+            Ok(None) => return Ok(None),
+            Ok(Some(code)) => Ok(code),
+            Err(err) => Err(err),
+        };
+        let (exit_code, exception_value) = process_outcome(py, outcome)?;
+        self.exit_code = Some(exit_code);
+        self.exception_value = exception_value;
+        Ok(self.exit_code)
+    }
+}
+
+#[pymethods]
+impl PyVm {
+    #[new]
+    #[pyo3(signature = (state, capabilities = 0, libs = Vec::new()))]
+    fn create(py: Python<'_>, state: PyVmState, capabilities: u64, libs: Vec<PyDictionary>) -> PyResult<Self> {
+        Ok(Self {
+            engine: create_engine(py, state, capabilities, libs)?,
+            exit_code: None,
+            exception_value: None,
+        })
+    }
+    fn step(&mut self, py: Python<'_>) -> PyResult<Option<i32>> {
+        self.step_once(py)
+    }
+    #[pyo3(signature = (max_steps = None))]
+    fn run(&mut self, py: Python<'_>, max_steps: Option<u32>) -> PyResult<Option<i32>> {
+        let mut steps = 0;
+        while max_steps.is_none_or(|max| steps < max) {
+            if let Some(code) = self.step_once(py)? {
+                return Ok(Some(code))
+            }
+            steps += 1;
+        }
+        Ok(None)
+    }
+    fn run_until(slf: &pyo3::PyCell<Self>, py: Python<'_>, predicate: PyObject) -> PyResult<Option<i32>> {
+        loop {
+            if let Some(code) = slf.borrow_mut().step_once(py)? {
+                return Ok(Some(code))
+            }
+            if predicate.call1(py, (slf,))?.is_true(py)? {
+                return Ok(None)
+            }
+        }
+    }
+    #[getter]
+    fn finished(&self) -> bool {
+        self.exit_code.is_some()
+    }
+    #[getter]
+    fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+    #[getter]
+    fn exception_value(&self, py: Python<'_>) -> Option<PyObject> {
+        self.exception_value.as_ref().map(|value| value.clone_ref(py))
+    }
+    #[getter]
+    fn cc(&self, py: Python<'_>) -> PyResult<PyContinuation> {
+        PyContinuation::new(py, self.engine.cc())
+    }
+    #[getter]
+    fn stack(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        let mut stack = Vec::new();
+        for item in self.engine.cc().stack.iter() {
+            stack.push(convert_from_vm(py, item)?)
+        }
+        Ok(stack)
+    }
+    #[getter]
+    fn regs(&self) -> PySaveList {
+        PySaveList::new(self.engine.ctrls().clone())
+    }
+    fn ctrl(&self, py: Python<'_>, index: usize) -> PyResult<Option<PyObject>> {
+        match self.engine.ctrls().get(index) {
+            Some(item) => convert_from_vm(py, item).map(Some),
+            None => Ok(None),
+        }
+    }
+    #[getter]
+    fn gas(&self) -> PyGas {
+        PyGas::new(self.engine.get_gas().clone())
+    }
+    #[getter]
+    fn steps(&self) -> u32 {
+        self.engine.steps()
+    }
+    fn state(&self, py: Python<'_>) -> PyResult<PyVmState> {
+        engine_state(py, &self.engine)
+    }
+    fn result(&self, py: Python<'_>) -> PyResult<PyVmResult> {
+        let Some(exit_code) = self.exit_code
+            else { return err!("execution is not finished yet") };
+        Ok(PyVmResult {
+            state: engine_state(py, &self.engine)?,
+            exit_code,
+            exception_value: self.exception_value(py),
+            trace: None,
+            interrupted: false,
+            breakpoint: None,
+            profile: None,
+        })
+    }
+}
+
 fn trace_callback(_engine: &Engine, info: &EngineTraceInfo) {
     use ton_vm::executor::EngineTraceInfoType::*;
     match &info.info_type {